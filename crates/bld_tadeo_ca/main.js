@@ -11,6 +11,12 @@ let scramble = document.querySelector("#scramble");
 /** @type {HTMLInputElement} */
 let lettering = document.querySelector("#lettering");
 
+/** @type {HTMLSelectElement} */
+let colorScheme = document.querySelector("#colorscheme");
+
+/** @type {HTMLSelectElement} */
+let mask = document.querySelector("#mask");
+
 /** @type {HTMLOutputElement} */
 let memo = document.querySelector("#memo");
 
@@ -35,7 +41,7 @@ function update() {
     }
 
     try {
-        preview.innerHTML = display(N, scramble.value);
+        preview.innerHTML = display(N, scramble.value, colorScheme.value, mask.value);
     } catch {
         preview.replaceChildren();
     }
@@ -44,6 +50,8 @@ function update() {
 n.addEventListener("input", update);
 scramble.addEventListener("input", update);
 lettering.addEventListener("input", update);
+colorScheme.addEventListener("input", update);
+mask.addEventListener("input", update);
 document.querySelector("[id=edgebuffer]").addEventListener("input", update);
 document.querySelector("[id=cornerbuffer]").addEventListener("input", update);
 document.querySelector("[id=wingbuffer]").addEventListener("input", update);
@@ -1,7 +1,10 @@
 #![no_std]
 extern crate alloc;
 
-use cube::{CornerSticker, Cube, EdgeOrientation, EdgeSticker, Face, WingSticker};
+use cube::{
+    ColorScheme, CornerSticker, Cube, EdgeOrientation, EdgeSticker, Face, StickerMask, StickerState,
+    WingSticker,
+};
 use cube_bld::{memo, memo_centers, Pieces, Sticker};
 use cube_notation::ParseMode;
 use alloc::{collections::BTreeMap as Map, fmt::Write, format, string::String, vec::Vec};
@@ -26,10 +29,31 @@ fn apply_alg(cube: &mut Cube, alg: &str) {
     }
 }
 
+/// Returns the [`StickerMask`] preset called `name` for `cube`.
+fn mask_preset(cube: &Cube, name: &str) -> Result<StickerMask, &'static str> {
+    Ok(match name {
+        "" | "none" => StickerMask::new(cube.n),
+        "last-layer" => StickerMask::last_layer(cube.n),
+        "oll" => StickerMask::oll(cube),
+        "f2l-fr" => StickerMask::f2l_slot(cube.n, Face::F, Face::R),
+        "f2l-fl" => StickerMask::f2l_slot(cube.n, Face::F, Face::L),
+        "f2l-br" => StickerMask::f2l_slot(cube.n, Face::B, Face::R),
+        "f2l-bl" => StickerMask::f2l_slot(cube.n, Face::B, Face::L),
+        _ => return Err("unknown sticker mask"),
+    })
+}
+
+/// Renders the scrambled cube as a grid of divs.
+///
+/// `color_scheme` is parsed with [`ColorScheme::from_str`](core::str::FromStr),
+/// so it can be a preset name or six hex colors. `mask` is one of `none`,
+/// `last-layer`, `oll` or `f2l-fr` and the like for the other slots.
 #[wasm_bindgen]
-pub fn display(n: u16, scramble: &str) -> String {
+pub fn display(n: u16, scramble: &str, color_scheme: &str, mask: &str) -> Result<String, String> {
+    let color_scheme = color_scheme.parse::<ColorScheme>()?;
     let mut cube = Cube::new_solved(n);
     apply_alg(&mut cube, scramble);
+    let mask = mask_preset(&cube, mask)?;
 
     let mut s = String::new();
     let height = 3 * usize::from(cube.n);
@@ -54,18 +78,16 @@ pub fn display(n: u16, scramble: &str) -> String {
                 let x = if n % 2 == 0 && x > n / 2 { x - 1 } else { x };
                 let y = if n % 2 == 0 && y > n / 2 { y - 1 } else { y };
 
-                let color = cube.color_at(face, adj_x, adj_y);
-                let color_name = match color {
-                    Face::U => "white",
-                    Face::L => "orange",
-                    Face::F => "green",
-                    Face::R => "red",
-                    Face::B => "blue",
-                    Face::D => "yellow",
+                let state = mask.get(face, adj_x, adj_y);
+                let color = state.color(color_scheme.color(cube.color_at(face, adj_x, adj_y)));
+                let highlight = if state == StickerState::Highlighted {
+                    format!("; box-shadow: inset 0 0 0 3px {}", StickerMask::HIGHLIGHT)
+                } else {
+                    String::new()
                 };
 
                 s.push_str(&format!(
-                    r#"<div style="background-color: {color_name}; grid-row-start: {y}; grid-row-end: {y}; grid-column-start: {x}; grid-column-end: {x}"></div>"#,
+                    r#"<div style="background-color: {color}; grid-row-start: {y}; grid-row-end: {y}; grid-column-start: {x}; grid-column-end: {x}{highlight}"></div>"#,
                     x = x_offset * cube.n + x + 1,
                     y = y_offset * cube.n + y + 1,
                 ));
@@ -75,7 +97,7 @@ pub fn display(n: u16, scramble: &str) -> String {
 
     s.push_str("</div>");
 
-    s
+    Ok(s)
 }
 
 #[wasm_bindgen]
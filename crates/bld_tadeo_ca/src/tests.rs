@@ -78,3 +78,21 @@ fn memorize_test() {
         2,
     ));
 }
+
+#[test]
+fn display_color_scheme() {
+    let html = display(1, "", "japanese", "none").unwrap();
+    expect![[r#"<div style="background-color: black; display: grid; grid-template-columns: repeat(4, 1fr); grid-template-rows: repeat(3, 1fr); width: 600px; height: 400px"><div style="background-color: #ffffff; grid-row-start: 1; grid-row-end: 1; grid-column-start: 2; grid-column-end: 2"></div><div style="background-color: #ff7f00; grid-row-start: 2; grid-row-end: 2; grid-column-start: 1; grid-column-end: 1"></div><div style="background-color: #00ff00; grid-row-start: 2; grid-row-end: 2; grid-column-start: 2; grid-column-end: 2"></div><div style="background-color: #ff0000; grid-row-start: 2; grid-row-end: 2; grid-column-start: 3; grid-column-end: 3"></div><div style="background-color: #ffff00; grid-row-start: 2; grid-row-end: 2; grid-column-start: 4; grid-column-end: 4"></div><div style="background-color: #0000ff; grid-row-start: 3; grid-row-end: 3; grid-column-start: 2; grid-column-end: 2"></div></div>"#]]
+    .assert_eq(&html);
+    assert!(display(1, "", "purple", "none").is_err());
+}
+
+#[test]
+fn display_mask() {
+    let html = display(3, "", "western", "f2l-fr").unwrap();
+    assert_eq!(html.matches("#808080").count(), 21);
+    assert_eq!(html.matches("box-shadow").count(), 5);
+    let html = display(3, "R", "western", "oll").unwrap();
+    assert_eq!(html.matches("#ffffff").count(), 7);
+    assert!(display(3, "", "western", "pll").is_err());
+}
@@ -2,11 +2,12 @@ use anyhow::{Context as _, Result};
 use calamine::{open_workbook_auto, DataType, Reader};
 use cube::{CornerSticker, Cube, EdgeSticker, WingSticker};
 use cube_bld::{memo, Orientation, Permutation, Pieces};
-use cube_notation::{canonicalize, format_moves, parse_alg, ParseMode, Tree};
+use cube_notation::{
+    canonicalize, find_commutator, format_moves, parse_alg, Ergonomics, ParseMode, Tree,
+};
 use indicatif::{MultiProgress, ParallelProgressIterator, ProgressBar, ProgressStyle};
 use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 use reqwest::blocking::Client;
-use rquickjs::{CatchResultExt, Context, Runtime, Undefined};
 use std::{
     collections::BTreeMap,
     fmt::{Display, Write as _},
@@ -129,20 +130,23 @@ fn main() -> Result<()> {
     all_algs.sort();
     all_algs.dedup();
 
+    let ergonomics = Ergonomics::default();
     let mut map: BTreeMap<
         String,
-        BTreeMap<Case, BTreeMap<String, BTreeMap<String, Vec<Arc<Source>>>>>,
+        BTreeMap<Case, BTreeMap<String, (f32, BTreeMap<String, Vec<Arc<Source>>>)>>,
     > = BTreeMap::new();
     for (case, alg, source) in all_algs {
         let tree = parse_alg(case.n(), ParseMode::Wca, &alg).unwrap();
-        let canonical = format_moves(&canonicalize(&tree.to_moves()));
+        let moves = canonicalize(&tree.to_moves());
+        let canonical = format_moves(&moves);
         let fancy = tree.to_string();
         map.entry(case.category_name().to_owned())
             .or_default()
             .entry(case)
             .or_default()
             .entry(canonical)
-            .or_default()
+            .or_insert_with(|| (ergonomics.score(&moves).total, BTreeMap::new()))
+            .1
             .entry(fancy)
             .or_default()
             .push(source);
@@ -157,25 +161,29 @@ fn main() -> Result<()> {
             let mut obj = write_json::object(&mut out);
             let mut obj = obj.object("cases");
             for (case, canonicals) in cases {
-                let mut obj = obj.object(&case.to_string());
-                {
-                    for (canonical, variants) in canonicals {
-                        let mut obj = obj.object(&canonical);
-                        for (variant, mut sources) in variants {
-                            let mut arr = obj.array(&variant);
-                            sources.sort();
-                            sources.dedup();
-                            for source in sources {
-                                let i = source_cache
-                                    .iter()
-                                    .position(|x| Arc::ptr_eq(&source, x))
-                                    .unwrap_or_else(|| {
-                                        let i = source_cache.len();
-                                        source_cache.push(source.clone());
-                                        i
-                                    });
-                                arr.number(i as f64);
-                            }
+                // The algs of a case are ranked by their ergonomics score,
+                // easiest first.
+                let mut canonicals = canonicals.into_iter().collect::<Vec<_>>();
+                canonicals.sort_by(|(_, (a, _)), (_, (b, _))| a.total_cmp(b));
+                let mut arr = obj.array(&case.to_string());
+                for (canonical, (score, variants)) in canonicals {
+                    let mut obj = arr.object();
+                    obj.string("alg", &canonical).number("score", score as f64);
+                    let mut obj = obj.object("variants");
+                    for (variant, mut sources) in variants {
+                        let mut arr = obj.array(&variant);
+                        sources.sort();
+                        sources.dedup();
+                        for source in sources {
+                            let i = source_cache
+                                .iter()
+                                .position(|x| Arc::ptr_eq(&source, x))
+                                .unwrap_or_else(|| {
+                                    let i = source_cache.len();
+                                    source_cache.push(source.clone());
+                                    i
+                                });
+                            arr.number(i as f64);
                         }
                     }
                 }
@@ -318,43 +326,16 @@ fn make_blddb_iter(
                                 .with_message(format!("BLDDB: {name}")),
                         ),
                     )
-                    .map_init(
-                        || {
-                            let runtime = Runtime::new().unwrap();
-                            let context = Context::full(&runtime).unwrap();
-                            context
-                                .with(|ctx| {
-                                    Undefined = ctx
-                                        .eval(include_bytes!("commutator.js").as_slice())
-                                        .catch(&ctx)
-                                        .map_err(|e| anyhow::format_err!("{e:?}"))?;
-                                    Ok::<_, anyhow::Error>(())
-                                })
-                                .unwrap();
-                            (runtime, context)
-                        },
-                        |(_runtime, context), alg| {
-                            let converted_alg = context
-                                .with(|ctx| {
-                                    ctx.globals()
-                                        .get::<_, rquickjs::Function>("commutator")?
-                                        .call((&alg,))
-                                })
-                                .unwrap();
-
-                            let alg = if converted_alg == "Not found." {
-                                alg
-                            } else {
-                                converted_alg
-                            };
-
-                            let mut algs = Vec::new();
-                            collect(&alg, &Heuristics::compute(&source), |case, alg| {
-                                algs.push((case, alg.to_string(), source.clone()));
-                            });
-                            algs
-                        },
-                    )
+                    .map(|alg| {
+                        let mut algs = Vec::new();
+                        collect(&alg, &Heuristics::compute(&source), |case, tree| {
+                            // The tree is parsed at the size of the case.
+                            let commutator = find_commutator(&tree.to_moves());
+                            let alg = commutator.as_ref().unwrap_or(tree).to_string();
+                            algs.push((case, alg, source.clone()));
+                        });
+                        algs
+                    })
                     .flatten_iter()
                     .collect::<Vec<_>>())
             }
@@ -596,7 +577,7 @@ fn collect(
     mut f: impl FnMut(Case, &Tree),
 ) {
     if is_possibly_3x3 {
-        if let Ok(tree) = parse_alg(3, ParseMode::Wca, line) {
+        if let Ok(tree) = parse_alg(3, ParseMode::Lenient, line) {
             let mut cube = Cube::new_solved(3);
             tree.apply_inverse_to(&mut cube);
             if cube.corners.are_solved() {
@@ -617,8 +598,14 @@ fn collect(
         }
     }
 
+    // Lowercase moves are wide moves on a 3x3x3 but inner slices on bigger
+    // cubes, so only the bigger cubes share one parse of the line, which is
+    // retargeted to each of them.
+    let big = parse_alg(7, ParseMode::Lenient, line).ok();
+    let retarget = |n| big.as_ref().and_then(|tree| tree.retarget(n));
+
     if is_possibly_4x4 {
-        if let Ok(tree) = parse_alg(4, ParseMode::Wca, line) {
+        if let Some(tree) = retarget(4) {
             let mut cube = Cube::new_solved(4);
             tree.apply_inverse_to(&mut cube);
             if cube.corners.are_solved() && cube.edges.are_solved() {
@@ -645,7 +632,7 @@ fn collect(
     }
 
     if is_possibly_5x5 {
-        if let Ok(tree) = parse_alg(5, ParseMode::Wca, line) {
+        if let Some(tree) = retarget(5) {
             let mut cube = Cube::new_solved(5);
             tree.apply_inverse_to(&mut cube);
             if cube.corners.are_solved()
@@ -669,7 +656,7 @@ fn collect(
     if is_probably_midges || is_probably_tcenters {
         if let Ok(tree) = parse_alg(
             5,
-            ParseMode::Wca,
+            ParseMode::Lenient,
             &line.replace('M', "m").replace('E', "e").replace('S', "s"),
         ) {
             let mut cube = Cube::new_solved(5);
@@ -693,7 +680,7 @@ fn collect(
     }
 
     if is_possibly_6x6 {
-        if let Ok(tree) = parse_alg(6, ParseMode::Wca, line) {
+        if let Some(tree) = retarget(6) {
             let mut cube = Cube::new_solved(6);
             tree.apply_inverse_to(&mut cube);
             if cube.corners.are_solved()
@@ -0,0 +1,52 @@
+//! Compares applying random face turns to a [`Cube`] and to a [`PackedCube`].
+//!
+//! Run with `cargo bench -p cube`.
+
+use cube::{Cube, Face, PackedCube, PackedMoveTable};
+use oorandom::Rand32;
+use std::{hint::black_box, time::Instant};
+
+const MOVES: usize = 10_000_000;
+
+fn main() {
+    let mut rand = Rand32::new(0);
+    let moves = (0..MOVES)
+        .map(|_| {
+            let face = Face::from_index(rand.rand_range(0..6) as usize);
+            let count = rand.rand_range(1..4) as u8;
+            (face, count)
+        })
+        .collect::<Vec<_>>();
+
+    let mut cube = Cube::new_solved(3);
+    let start = Instant::now();
+    for &(face, count) in &moves {
+        black_box(&mut cube).rotate(face, 0..1, count);
+    }
+    report("Cube::rotate", start);
+
+    let mut cube = Cube::new_solved(3);
+    let start = Instant::now();
+    for &(face, count) in &moves {
+        black_box(&mut cube).rotate_face(face, count);
+    }
+    report("Cube::rotate_face", start);
+
+    let table = PackedMoveTable::new();
+    let mut packed = PackedCube::SOLVED;
+    let start = Instant::now();
+    for &(face, count) in &moves {
+        black_box(&mut packed).rotate_face(&table, face, count);
+    }
+    report("PackedCube::rotate_face", start);
+
+    assert_eq!(packed.to_cube(), cube);
+}
+
+fn report(name: &str, start: Instant) {
+    let elapsed = start.elapsed();
+    println!(
+        "{name}: {elapsed:?} for {MOVES} moves ({:.2} ns/move)",
+        elapsed.as_nanos() as f64 / MOVES as f64
+    );
+}
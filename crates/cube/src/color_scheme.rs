@@ -0,0 +1,152 @@
+use crate::Face;
+use core::{fmt, str::FromStr};
+
+/// A color in the sRGB color space.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const fn new(r: u8, g: u8, b: u8) -> Rgb {
+        Rgb { r, g, b }
+    }
+
+    /// Returns the red, green and blue components scaled to `0.0..=1.0`.
+    pub fn to_f32(self) -> [f32; 3] {
+        [self.r, self.g, self.b].map(|c| f32::from(c) / 255.0)
+    }
+}
+
+/// Formats the color as a CSS hex color like `#ff7f00`.
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+impl FromStr for Rgb {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Rgb, &'static str> {
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        if hex.len() != 6 || !hex.is_ascii() {
+            return Err("invalid color: expected six hex digits");
+        }
+        let component = |i: usize| {
+            u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| "invalid color: not a hex digit")
+        };
+        Ok(Rgb::new(component(0)?, component(2)?, component(4)?))
+    }
+}
+
+/// The colors of the six faces of a cube.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ColorScheme {
+    /// The color of each face, indexed by [`Face::index`].
+    pub colors: [Rgb; 6],
+}
+
+impl ColorScheme {
+    /// White on top, green in front, yellow opposite white and blue opposite green.
+    pub const WESTERN: ColorScheme = ColorScheme {
+        colors: [
+            Rgb::new(255, 255, 255),
+            Rgb::new(255, 127, 0),
+            Rgb::new(0, 255, 0),
+            Rgb::new(255, 0, 0),
+            Rgb::new(0, 0, 255),
+            Rgb::new(255, 255, 0),
+        ],
+    };
+
+    /// Like [`ColorScheme::WESTERN`], but with blue opposite white and yellow
+    /// opposite green.
+    pub const JAPANESE: ColorScheme = ColorScheme {
+        colors: [
+            Rgb::new(255, 255, 255),
+            Rgb::new(255, 127, 0),
+            Rgb::new(0, 255, 0),
+            Rgb::new(255, 0, 0),
+            Rgb::new(255, 255, 0),
+            Rgb::new(0, 0, 255),
+        ],
+    };
+
+    /// The Western scheme with colors from the Okabe-Ito palette, which stay
+    /// distinguishable with the common forms of color blindness.
+    pub const COLORBLIND: ColorScheme = ColorScheme {
+        colors: [
+            Rgb::new(255, 255, 255),
+            Rgb::new(230, 159, 0),
+            Rgb::new(0, 158, 115),
+            Rgb::new(213, 94, 0),
+            Rgb::new(0, 114, 178),
+            Rgb::new(240, 228, 66),
+        ],
+    };
+
+    pub const fn color(&self, face: Face) -> Rgb {
+        self.colors[face.index()]
+    }
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        ColorScheme::WESTERN
+    }
+}
+
+/// Parses either the name of a preset (`western`, `japanese` or
+/// `colorblind`) or six hex colors for U, L, F, R, B and D, separated by
+/// commas or spaces.
+impl FromStr for ColorScheme {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<ColorScheme, &'static str> {
+        match s.trim() {
+            "western" => return Ok(ColorScheme::WESTERN),
+            "japanese" => return Ok(ColorScheme::JAPANESE),
+            "colorblind" => return Ok(ColorScheme::COLORBLIND),
+            _ => {}
+        }
+
+        let mut colors = [Rgb::new(0, 0, 0); 6];
+        let mut parts = s
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|part| !part.is_empty());
+        for color in &mut colors {
+            *color = parts
+                .next()
+                .ok_or("invalid color scheme: expected six colors")?
+                .parse()?;
+        }
+        if parts.next().is_some() {
+            return Err("invalid color scheme: expected six colors");
+        }
+        Ok(ColorScheme { colors })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn parse() {
+        assert_eq!("western".parse(), Ok(ColorScheme::WESTERN));
+        assert_eq!(
+            "#ffffff,#ff7f00,#00ff00,#ff0000,#ffff00,#0000ff".parse(),
+            Ok(ColorScheme::JAPANESE)
+        );
+        assert_eq!(
+            "ffffff ff7f00 00ff00 ff0000 0000ff".parse::<ColorScheme>(),
+            Err("invalid color scheme: expected six colors")
+        );
+        assert_eq!("#00ff0g".parse::<Rgb>(), Err("invalid color: not a hex digit"));
+        assert_eq!(ColorScheme::WESTERN.color(Face::L).to_string(), "#ff7f00");
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    map_orientation, orientation_after_move, AnySticker, CornerOrientation, CornerPermutation, CornerSticker, Corners, EdgeOrientation, EdgePermutation, EdgeSticker, Edges, Face, Handedness, Obliques, ObliquesPair, TCenters, Wings, XCenters
+    map_orientation, orientation_after_move, AnySticker, ColorScheme, CornerOrientation, CornerPermutation, CornerSticker, Corners, EdgeOrientation, EdgePermutation, EdgeSticker, Edges, Face, Handedness, Obliques, ObliquesPair, Rgb, StickerMask, StickerState, TCenters, Wings, XCenters
 };
 use alloc::format;
 use alloc::string::String;
@@ -401,8 +401,18 @@ impl CubeLayer {
 }
 
 impl Cube {
-    #[allow(unused)]
     pub fn ansi_repr(&self) -> String {
+        self.ansi_repr_with_color_scheme(&ColorScheme::WESTERN)
+    }
+
+    pub fn ansi_repr_with_color_scheme(&self, color_scheme: &ColorScheme) -> String {
+        self.ansi_repr_masked(color_scheme, &StickerMask::new(self.n))
+    }
+
+    /// Like [`Cube::ansi_repr_with_color_scheme`], but draws greyed out
+    /// stickers in grey and shows the letter of highlighted stickers.
+    pub fn ansi_repr_masked(&self, color_scheme: &ColorScheme, mask: &StickerMask) -> String {
+        assert_eq!(mask.n(), self.n);
         let n = self.n;
         let cube = self;
         let mut s = String::new();
@@ -427,19 +437,23 @@ impl Cube {
                     let y = if n % 2 == 0 && y > n / 2 { y - 1 } else { y };
 
                     let color = cube.color_at(face, adj_x, adj_y);
+                    let state = mask.get(face, adj_x, adj_y);
+                    let background = state.color(color_scheme.color(color));
+                    let foreground = match state {
+                        StickerState::Highlighted => StickerMask::HIGHLIGHT,
+                        StickerState::Visible | StickerState::Grey => background,
+                    };
+                    let Rgb { r, g, b } = background;
+                    let Rgb {
+                        r: fg_r,
+                        g: fg_g,
+                        b: fg_b,
+                    } = foreground;
 
                     grid[(((y_offset * usize::from(self.n) + usize::from(y)) * width)
                         + x_offset * usize::from(self.n)
                         + usize::from(x))] = format!(
-                        "\x1b[{}m{color:?} \x1b[0m",
-                        match color {
-                            Face::U => "38;2;255;255;255;48;2;255;255;255",
-                            Face::L => "38;2;255;127;0;48;2;255;127;0",
-                            Face::F => "38;2;0;255;0;48;2;0;255;0",
-                            Face::R => "38;2;255;0;0;48;2;255;0;0",
-                            Face::B => "38;2;0;0;255;48;2;0;0;255",
-                            Face::D => "38;2;255;255;0;48;2;255;255;0",
-                        }
+                        "\x1b[38;2;{fg_r};{fg_g};{fg_b};48;2;{r};{g};{b}m{color:?} \x1b[0m"
                     );
                 }
             }
@@ -206,6 +206,10 @@ impl EdgePermutation {
     pub const fn index(self) -> usize {
         self as usize
     }
+
+    pub const fn from_index(num: usize) -> EdgePermutation {
+        EdgePermutation::SOLVED[num]
+    }
 }
 
 impl EdgeOrientation {
@@ -0,0 +1,361 @@
+//! Exports the moves of an n by n cube as permutations of its stickers.
+//!
+//! Stickers are numbered face by face in U, L, F, R, B, D order, and row by
+//! row from the top left of each face as laid out in [`Cube::ansi_repr`].
+//! Every permutation is derived from [`Cube::rotate`], so the exported puzzle
+//! always agrees with the rest of the crate.
+//!
+//! Moves are named `R` for the outer layer and `R_2`, `R_3` and so on for the
+//! slices behind it. The middle slices of odd cubes are left out, since
+//! [`Cube`] keeps its centers fixed and turns the rest of the cube instead.
+
+use crate::{for_each_sticker, AnySticker, Cube, Face, Handedness};
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::Write;
+
+/// Returns the face and [`AnySticker`] of every sticker, in export order.
+fn stickers(n: u16) -> Vec<(Face, AnySticker)> {
+    let mut stickers = Vec::new();
+    for_each_sticker(n, |face, x, y| {
+        stickers.push((face, AnySticker::at(n, face, x, y)))
+    });
+    stickers
+}
+
+/// Returns the sticker that started out where `sticker` is in `cube`.
+fn home(cube: &Cube, sticker: &AnySticker) -> AnySticker {
+    match *sticker {
+        AnySticker::Center(face) => AnySticker::Center(face),
+        AnySticker::Edge(position) => AnySticker::Edge(cube.edges.at(position)),
+        AnySticker::Corner(position) => AnySticker::Corner(cube.corners.at(position)),
+        AnySticker::Wing(layer, position) => {
+            AnySticker::Wing(layer, cube.layers[usize::from(layer)].wings.at(position))
+        }
+        AnySticker::TCenter(layer, position) => {
+            AnySticker::TCenter(layer, cube.layers[usize::from(layer)].tcenters.at(position))
+        }
+        AnySticker::XCenter(layer, position) => {
+            AnySticker::XCenter(layer, cube.layers[usize::from(layer)].xcenters.at(position))
+        }
+        AnySticker::Oblique(layer, index, position, handedness) => {
+            let pair = &cube.layers[usize::from(layer)].obliques[usize::from(index)];
+            let obliques = match handedness {
+                Handedness::Left => &pair.left,
+                Handedness::Right => &pair.right,
+            };
+            AnySticker::Oblique(layer, index, obliques.at(position), handedness)
+        }
+    }
+}
+
+/// Returns the faces and layers of every exported move.
+pub fn moves(n: u16) -> impl Iterator<Item = (Face, u16)> {
+    Face::ALL
+        .into_iter()
+        .flat_map(move |face| (0..n / 2).map(move |layer| (face, layer)))
+}
+
+/// Returns the name of a clockwise turn of `layer` counted from `face`.
+pub fn move_name(face: Face, layer: u16) -> String {
+    if layer == 0 {
+        format!("{face:?}")
+    } else {
+        format!("{face:?}_{}", layer + 1)
+    }
+}
+
+/// Returns, for every sticker, the index of the sticker that a clockwise turn
+/// of `layer` counted from `face` moves to its place.
+pub fn sticker_permutation(n: u16, face: Face, layer: u16) -> Vec<usize> {
+    let mut cube = Cube::new_solved(n);
+    cube.rotate(face, layer..layer + 1, 1);
+    let stickers = stickers(n);
+    stickers
+        .iter()
+        .map(|(_, sticker)| {
+            let home = home(&cube, sticker);
+            stickers
+                .iter()
+                .position(|(_, other)| *other == home)
+                .expect("every sticker should have a home")
+        })
+        .collect()
+}
+
+/// Writes `permutation` in cycle notation with one-based points, where
+/// `permutation[i]` is the point that is moved to `i`.
+fn write_cycles(s: &mut String, permutation: &[usize]) {
+    let mut image = alloc::vec![0; permutation.len()];
+    for (to, &from) in permutation.iter().enumerate() {
+        image[from] = to;
+    }
+    let mut seen = alloc::vec![false; permutation.len()];
+    let mut empty = true;
+    for start in 0..permutation.len() {
+        if seen[start] || image[start] == start {
+            continue;
+        }
+        empty = false;
+        s.push('(');
+        let mut point = start;
+        loop {
+            seen[point] = true;
+            write!(s, "{}", point + 1).unwrap();
+            point = image[point];
+            if point == start {
+                break;
+            }
+            s.push(',');
+        }
+        s.push(')');
+    }
+    if empty {
+        s.push_str("()");
+    }
+}
+
+/// Returns the moves of an `n` by `n` cube as GAP permutations, along with
+/// the group they generate.
+pub fn gap(n: u16) -> String {
+    let mut s = String::new();
+    writeln!(
+        s,
+        "# Moves of the {n}x{n}x{n} cube as permutations of its {} stickers.",
+        6 * usize::from(n) * usize::from(n)
+    )
+    .unwrap();
+    let mut names = Vec::new();
+    for (face, layer) in moves(n) {
+        let name = move_name(face, layer);
+        write!(s, "{name} := ").unwrap();
+        write_cycles(&mut s, &sticker_permutation(n, face, layer));
+        s.push_str(";\n");
+        names.push(name);
+    }
+    if names.is_empty() {
+        writeln!(s, "Cube{n} := Group(());").unwrap();
+    } else {
+        writeln!(s, "Cube{n} := Group({});", names.join(", ")).unwrap();
+    }
+    s
+}
+
+/// Returns the name of the ksolve orbit that `sticker` belongs to, or `None`
+/// for the fixed centers of odd cubes.
+fn orbit_name(sticker: &AnySticker) -> Option<String> {
+    Some(match *sticker {
+        AnySticker::Center(_) => return None,
+        AnySticker::Edge(_) => String::from("EDGES"),
+        AnySticker::Corner(_) => String::from("CORNERS"),
+        AnySticker::Wing(layer, _) => format!("WINGS_{}", layer + 1),
+        AnySticker::TCenter(layer, _) => format!("TCENTERS_{}", layer + 1),
+        AnySticker::XCenter(layer, _) => format!("XCENTERS_{}", layer + 1),
+        AnySticker::Oblique(layer, index, _, Handedness::Left) => {
+            format!("LEFT_OBLIQUES_{}_{}", layer + 1, index + 1)
+        }
+        AnySticker::Oblique(layer, index, _, Handedness::Right) => {
+            format!("RIGHT_OBLIQUES_{}_{}", layer + 1, index + 1)
+        }
+    })
+}
+
+/// Returns whether the stickers of an orbit are told apart only by color.
+fn is_center(sticker: &AnySticker) -> bool {
+    matches!(
+        sticker,
+        AnySticker::TCenter(..) | AnySticker::XCenter(..) | AnySticker::Oblique(..)
+    )
+}
+
+/// Returns a ksolve puzzle definition for an `n` by `n` cube.
+///
+/// Every kind of sticker gets its own set with a single orientation. Center
+/// stickers of the same color are identical pieces in the solved state.
+pub fn ksolve(n: u16) -> String {
+    let stickers = stickers(n);
+
+    // The sticker indices of every orbit, in order of first appearance.
+    let mut orbits: Vec<(String, Vec<usize>)> = Vec::new();
+    for (i, (_, sticker)) in stickers.iter().enumerate() {
+        let Some(name) = orbit_name(sticker) else {
+            continue;
+        };
+        match orbits.iter_mut().find(|(other, _)| *other == name) {
+            Some((_, members)) => members.push(i),
+            None => orbits.push((name, alloc::vec![i])),
+        }
+    }
+
+    let mut s = String::new();
+    writeln!(s, "Name {n}x{n}x{n}").unwrap();
+    s.push('\n');
+    for (name, members) in &orbits {
+        writeln!(s, "Set {name} {} 1", members.len()).unwrap();
+    }
+
+    s.push_str("\nSolved\n");
+    for (name, members) in &orbits {
+        writeln!(s, "{name}").unwrap();
+        let pieces = members
+            .iter()
+            .enumerate()
+            .map(|(j, &i)| {
+                let (face, sticker) = &stickers[i];
+                if is_center(sticker) {
+                    face.index() + 1
+                } else {
+                    j + 1
+                }
+            })
+            .map(|piece| format!("{piece}"))
+            .collect::<Vec<_>>();
+        writeln!(s, "{}", pieces.join(" ")).unwrap();
+    }
+    s.push_str("End\n");
+
+    for (face, layer) in moves(n) {
+        let permutation = sticker_permutation(n, face, layer);
+        write!(s, "\nMove {}\n", move_name(face, layer)).unwrap();
+        for (name, members) in &orbits {
+            if members.iter().all(|&i| permutation[i] == i) {
+                continue;
+            }
+            writeln!(s, "{name}").unwrap();
+            let pieces = members
+                .iter()
+                .map(|&i| {
+                    let from = members
+                        .iter()
+                        .position(|&j| j == permutation[i])
+                        .expect("moves should stay within an orbit");
+                    format!("{}", from + 1)
+                })
+                .collect::<Vec<_>>();
+            writeln!(s, "{}", pieces.join(" ")).unwrap();
+            writeln!(s, "{}", alloc::vec!["0"; members.len()].join(" ")).unwrap();
+        }
+        s.push_str("End\n");
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use expect_test::expect;
+    use oorandom::Rand32;
+
+    #[test]
+    fn permutations_match_cube() {
+        for n in 1..=7 {
+            let stickers = stickers(n);
+            let moves = moves(n)
+                .map(|(face, layer)| (face, layer, sticker_permutation(n, face, layer)))
+                .collect::<Vec<_>>();
+            let mut rand = Rand32::new(u64::from(n));
+            let mut cube = Cube::new_solved(n);
+            let mut colors = stickers.iter().map(|&(face, _)| face).collect::<Vec<_>>();
+            for _ in 0..50 {
+                if moves.is_empty() {
+                    break;
+                }
+                let (face, layer, permutation) =
+                    &moves[rand.rand_range(0..moves.len() as u32) as usize];
+                cube.rotate(*face, *layer..*layer + 1, 1);
+                colors = permutation.iter().map(|&from| colors[from]).collect();
+                let mut i = 0;
+                for_each_sticker(n, |face, x, y| {
+                    assert_eq!(colors[i], cube.color_at(face, x, y));
+                    i += 1;
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn gap_2x2x2() {
+        expect![[r#"
+            # Moves of the 2x2x2 cube as permutations of its 24 stickers.
+            U := (1,2,4,3)(5,17,13,9)(6,18,14,10);
+            L := (1,9,21,20)(3,11,23,18)(5,6,8,7);
+            F := (3,13,22,8)(4,15,21,6)(9,10,12,11);
+            R := (2,19,22,10)(4,17,24,12)(13,14,16,15);
+            B := (1,7,24,14)(2,5,23,16)(17,18,20,19);
+            D := (7,11,15,19)(8,12,16,20)(21,22,24,23);
+            Cube2 := Group(U, L, F, R, B, D);
+        "#]]
+        .assert_eq(&gap(2));
+    }
+
+    #[test]
+    fn ksolve_3x3x3() {
+        expect![[r#"
+            Name 3x3x3
+
+            Set CORNERS 24 1
+            Set EDGES 24 1
+
+            Solved
+            CORNERS
+            1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24
+            EDGES
+            1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24
+            End
+
+            Move U
+            CORNERS
+            3 1 4 2 9 10 7 8 13 14 11 12 17 18 15 16 5 6 19 20 21 22 23 24
+            0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+            EDGES
+            2 4 1 3 9 6 7 8 13 10 11 12 17 14 15 16 5 18 19 20 21 22 23 24
+            0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+            End
+
+            Move L
+            CORNERS
+            20 2 18 4 7 5 8 6 1 10 3 12 13 14 15 16 17 23 19 21 9 22 11 24
+            0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+            EDGES
+            1 19 3 4 6 8 5 7 9 2 11 12 13 14 15 16 17 18 22 20 21 10 23 24
+            0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+            End
+
+            Move F
+            CORNERS
+            1 2 8 6 5 21 7 22 11 9 12 10 3 14 4 16 17 18 19 20 15 13 23 24
+            0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+            EDGES
+            1 2 3 7 5 6 21 8 10 12 9 11 13 4 15 16 17 18 19 20 14 22 23 24
+            0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+            End
+
+            Move R
+            CORNERS
+            1 10 3 12 5 6 7 8 9 22 11 24 15 13 16 14 4 18 2 20 21 19 23 17
+            0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+            EDGES
+            1 2 11 4 5 6 7 8 9 10 23 12 14 16 13 15 17 3 19 20 21 22 18 24
+            0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+            End
+
+            Move B
+            CORNERS
+            14 16 3 4 2 6 1 8 9 10 11 12 13 24 15 23 19 17 20 18 21 22 5 7
+            0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+            EDGES
+            15 2 3 4 5 1 7 8 9 10 11 12 13 14 24 16 18 20 17 19 21 22 23 6
+            0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+            End
+
+            Move D
+            CORNERS
+            1 2 3 4 5 6 19 20 9 10 7 8 13 14 11 12 17 18 15 16 23 21 24 22
+            0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+            EDGES
+            1 2 3 4 5 6 7 20 9 10 11 8 13 14 15 12 17 18 19 16 22 24 21 23
+            0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+            End
+        "#]]
+        .assert_eq(&ksolve(3));
+    }
+}
@@ -11,17 +11,22 @@
 //! track of its orientation, respectively.
 //!
 //! Note: Most of the abstractions in this crate are not meant to be extremely
-//! fast, yet. See [`corners::fixed`] for some preliminary work in this respect.
+//! fast, yet. See [`corners::fixed`] and [`packed`] for some preliminary work
+//! in this respect.
 
 #![no_std]
 extern crate alloc;
 #[cfg(test)]
 extern crate std;
 
+pub mod color_scheme;
 pub mod corners;
 pub mod cube;
 pub mod edges;
+pub mod export;
 pub mod obliques;
+pub mod packed;
+pub mod sticker_mask;
 pub mod tables;
 pub mod tcenters;
 pub mod wings;
@@ -30,6 +35,7 @@ pub mod xcenters;
 #[cfg(test)]
 mod tests;
 
+pub use color_scheme::{ColorScheme, Rgb};
 pub use corners::{
     fixed::{CornerCoordsFixed, CornerCoordsMoveTableFixed, CornerPermutationFixed, CornersFixed},
     CornerDirection, CornerOrientation, CornerPermutation, CornerSticker, Corners,
@@ -37,6 +43,8 @@ pub use corners::{
 pub use cube::{Cube, CubeLayer, RotatedCube};
 pub use edges::{EdgeDirection, EdgeOrientation, EdgePermutation, EdgeSticker, Edges};
 pub use obliques::{Obliques, ObliquesPair};
+pub use packed::{PackedCube, PackedMove, PackedMoveTable};
+pub use sticker_mask::{StickerMask, StickerState};
 pub use tcenters::TCenters;
 pub use wings::{WingSticker, Wings};
 pub use xcenters::XCenters;
@@ -215,6 +223,54 @@ where
     }
 }
 
+/// Returns the column and row of the sticker at the [`Cube::color_at`]
+/// coordinates `x` and `y`, counted from the top left of its face.
+fn column_and_row(n: u16, x: i16, y: i16) -> (u16, u16) {
+    let half_n = (n / 2) as i16;
+    let even = n.is_multiple_of(2);
+    let column = (x + half_n) as u16 - u16::from(even && x > 0);
+    let row = (half_n - y) as u16 - u16::from(even && y < 0);
+    (column, row)
+}
+
+/// Calls `f` with the [`Cube::color_at`] coordinates of every sticker on a
+/// face, along with its column and row counted from the top left, row by row.
+pub fn for_each_face_sticker(n: u16, mut f: impl FnMut(i16, i16, u16, u16)) {
+    let half_n = (n / 2) as i16;
+    for y in (-half_n..=half_n).rev() {
+        if y == 0 && n.is_multiple_of(2) {
+            continue;
+        }
+        for x in -half_n..=half_n {
+            if x == 0 && n.is_multiple_of(2) {
+                continue;
+            }
+            let (column, row) = column_and_row(n, x, y);
+            f(x, y, column, row);
+        }
+    }
+}
+
+/// Calls `f` with the [`Cube::color_at`] coordinates of every sticker, face by
+/// face in [`Face::ALL`] order and row by row from the top left.
+pub(crate) fn for_each_sticker(n: u16, mut f: impl FnMut(Face, i16, i16)) {
+    for face in Face::ALL {
+        for_each_face_sticker(n, |x, y, _, _| f(face, x, y));
+    }
+}
+
+/// Returns the index of a sticker in the order of [`for_each_sticker`].
+pub(crate) fn sticker_index(n: u16, face: Face, x: i16, y: i16) -> usize {
+    let half_n = (n / 2) as i16;
+    assert!(x.abs() <= half_n && y.abs() <= half_n, "sticker out of range");
+    assert!(
+        !n.is_multiple_of(2) || (x != 0 && y != 0),
+        "sticker out of range"
+    );
+    let (column, row) = column_and_row(n, x, y);
+    (face.index() * usize::from(n) + usize::from(row)) * usize::from(n) + usize::from(column)
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub(crate) enum AnySticker {
     Center(Face),
@@ -228,7 +284,7 @@ pub(crate) enum AnySticker {
 
 impl AnySticker {
     pub(crate) fn at(n: u16, face: Face, x: i16, y: i16) -> AnySticker {
-        if n % 2 == 0 {
+        if n.is_multiple_of(2) {
             assert_ne!(x, 0);
             assert_ne!(y, 0);
         }
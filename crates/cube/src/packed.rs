@@ -0,0 +1,301 @@
+//! A packed representation of the corners and edges of a 3x3x3 cube.
+//!
+//! [`PackedCube`] stores one byte per corner and five bits per edge, so the
+//! whole state fits in two `u64`s. Moves are applied with precomputed
+//! shuffles from a [`PackedMoveTable`], without any branches or bounds checks.
+
+use crate::{
+    CornerOrientation, CornerPermutation, Corners, Cube, EdgeOrientation, EdgePermutation, Edges,
+    Face,
+};
+
+/// Mask with the lowest bit of every corner byte set.
+const CORNER_LOW_BITS: u64 = 0x0101_0101_0101_0101;
+
+/// Corner orientation is stored above the three permutation bits.
+const CORNER_ORIENTATION_SHIFT: u32 = 3;
+
+/// Each edge takes up five bits: four for permutation and one for orientation.
+const EDGE_BITS: u32 = 5;
+
+const EDGE_MASK: u64 = (1 << EDGE_BITS) - 1;
+
+/// Edge orientation is stored above the four permutation bits.
+const EDGE_ORIENTATION_SHIFT: u32 = 4;
+
+/// The corners and edges of a 3x3x3 cube packed into two `u64`s.
+///
+/// Byte `i` of `corners` holds the [`CornerPermutation`] index of the piece
+/// in slot `i` in its low three bits and its [`CornerOrientation`] index in
+/// the next two. Bits `5 * i..5 * i + 5` of `edges` hold the
+/// [`EdgePermutation`] index of the piece in slot `i` and its
+/// [`EdgeOrientation`] index above it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PackedCube {
+    pub corners: u64,
+    pub edges: u64,
+}
+
+impl Default for PackedCube {
+    fn default() -> Self {
+        Self::SOLVED
+    }
+}
+
+impl PackedCube {
+    pub const SOLVED: PackedCube = {
+        let mut corners = 0;
+        let mut i = 0;
+        while i < 8 {
+            corners |= (i as u64) << (8 * i);
+            i += 1;
+        }
+        let mut edges = 0;
+        let mut i = 0;
+        while i < 12 {
+            edges |= (i as u64) << (EDGE_BITS * i);
+            i += 1;
+        }
+        PackedCube { corners, edges }
+    };
+
+    pub fn new(corners: &Corners, edges: &Edges) -> PackedCube {
+        let mut packed_corners = 0;
+        for i in 0..8 {
+            let piece = corners.permutation[i].index() as u64
+                | (corners.orientation[i].index() as u64) << CORNER_ORIENTATION_SHIFT;
+            packed_corners |= piece << (8 * i);
+        }
+        let mut packed_edges = 0;
+        for i in 0..12 {
+            let piece = edges.permutation[i].index() as u64
+                | (edges.orientation[i].index() as u64) << EDGE_ORIENTATION_SHIFT;
+            packed_edges |= piece << (EDGE_BITS as usize * i);
+        }
+        PackedCube {
+            corners: packed_corners,
+            edges: packed_edges,
+        }
+    }
+
+    /// Packs the corners and edges of a 3x3x3 cube.
+    pub fn from_cube(cube: &Cube) -> PackedCube {
+        assert_eq!(cube.n, 3);
+        PackedCube::new(&cube.corners, &cube.edges)
+    }
+
+    /// Returns a 3x3x3 cube with the corners and edges of this state.
+    pub fn to_cube(self) -> Cube {
+        let mut cube = Cube::new_solved(3);
+        cube.corners = self.to_corners();
+        cube.edges = self.to_edges();
+        cube
+    }
+
+    pub fn to_corners(self) -> Corners {
+        let mut corners = Corners::new();
+        for i in 0..8 {
+            let piece = (self.corners >> (8 * i)) & 0xff;
+            corners.permutation[i] = CornerPermutation::from_index((piece & 0x07) as usize);
+            corners.orientation[i] =
+                CornerOrientation::from_index((piece >> CORNER_ORIENTATION_SHIFT) as usize);
+        }
+        corners
+    }
+
+    pub fn to_edges(self) -> Edges {
+        let mut edges = Edges::new();
+        for i in 0..12 {
+            let piece = (self.edges >> (EDGE_BITS as usize * i)) & EDGE_MASK;
+            edges.permutation[i] = EdgePermutation::from_index((piece & 0x0f) as usize);
+            edges.orientation[i] =
+                EdgeOrientation::from_index((piece >> EDGE_ORIENTATION_SHIFT) as usize);
+        }
+        edges
+    }
+
+    pub fn apply(&mut self, mv: &PackedMove) {
+        let corners = mv.corners.shuffle(self.corners);
+        // Add the twists byte-wise, then subtract 3 from every orientation
+        // that ended up at 3 or 4.
+        let corners = corners + mv.corner_twists;
+        let orientation = (corners >> CORNER_ORIENTATION_SHIFT) & (CORNER_LOW_BITS * 0x07);
+        let wrapped = ((orientation + CORNER_LOW_BITS) >> 2) & CORNER_LOW_BITS;
+        self.corners = corners - ((wrapped * 3) << CORNER_ORIENTATION_SHIFT);
+
+        self.edges = mv.edges.shuffle(self.edges) ^ mv.edge_flips;
+    }
+
+    pub fn rotate_face(&mut self, table: &PackedMoveTable, face: Face, count: u8) {
+        self.apply(table.get(face, count));
+    }
+
+    pub fn is_solved(&self) -> bool {
+        *self == PackedCube::SOLVED
+    }
+}
+
+/// A permutation of the fields of a `u64`.
+///
+/// Fields that stay in place are kept with a single mask, and fields that
+/// move the same distance are moved together with one rotation. A face turn
+/// moves four pieces, each by a different distance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Shuffle {
+    keep: u64,
+    masks: [u64; 4],
+    rotations: [u32; 4],
+}
+
+impl Shuffle {
+    /// Builds the shuffle that moves the field at `sources[i]` to `i`.
+    fn new(field_bits: u32, field_mask: u64, sources: &[usize]) -> Shuffle {
+        let mut shuffle = Shuffle {
+            keep: 0,
+            masks: [0; 4],
+            rotations: [0; 4],
+        };
+        let mut len = 0;
+        for (to, &from) in sources.iter().enumerate() {
+            let mask = field_mask << (field_bits * from as u32);
+            if to == from {
+                shuffle.keep |= mask;
+                continue;
+            }
+            // Fields never wrap around, so rotating works in both directions.
+            let rotation = (field_bits * to as u32).wrapping_sub(field_bits * from as u32) % 64;
+            let group = match shuffle.rotations[..len].iter().position(|&r| r == rotation) {
+                Some(group) => group,
+                None => {
+                    assert!(len < 4, "too many distinct piece movements");
+                    shuffle.rotations[len] = rotation;
+                    len += 1;
+                    len - 1
+                }
+            };
+            shuffle.masks[group] |= mask;
+        }
+        shuffle
+    }
+
+    fn shuffle(&self, x: u64) -> u64 {
+        (x & self.keep)
+            | (x & self.masks[0]).rotate_left(self.rotations[0])
+            | (x & self.masks[1]).rotate_left(self.rotations[1])
+            | (x & self.masks[2]).rotate_left(self.rotations[2])
+            | (x & self.masks[3]).rotate_left(self.rotations[3])
+    }
+}
+
+/// A face turn as a precomputed shuffle of a [`PackedCube`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackedMove {
+    corners: Shuffle,
+    /// Orientation added to each corner byte, already shifted into place.
+    corner_twists: u64,
+    edges: Shuffle,
+    /// Orientation bits flipped in the edge word.
+    edge_flips: u64,
+}
+
+impl PackedMove {
+    /// Derives the shuffle for a face turn from [`Corners::rotate_face`] and
+    /// [`Edges::rotate_face`].
+    pub fn new(face: Face, count: u8) -> PackedMove {
+        let mut corners = Corners::new();
+        corners.rotate_face(face, count);
+        let mut edges = Edges::new();
+        edges.rotate_face(face, count);
+
+        // Starting from the solved state, the piece in each slot is the slot
+        // it was moved from, and its orientation is the twist it picked up.
+        let mut corner_twists = 0;
+        for (i, orientation) in corners.orientation.iter().enumerate() {
+            corner_twists |=
+                (orientation.index() as u64) << (8 * i as u32 + CORNER_ORIENTATION_SHIFT);
+        }
+        let mut edge_flips = 0;
+        for (i, orientation) in edges.orientation.iter().enumerate() {
+            edge_flips |=
+                (orientation.index() as u64) << (EDGE_BITS * i as u32 + EDGE_ORIENTATION_SHIFT);
+        }
+
+        PackedMove {
+            corners: Shuffle::new(8, 0xff, &corners.permutation.map(|p| p.index())),
+            corner_twists,
+            edges: Shuffle::new(EDGE_BITS, EDGE_MASK, &edges.permutation.map(|p| p.index())),
+            edge_flips,
+        }
+    }
+}
+
+/// All 18 face turns of a 3x3x3 cube as [`PackedMove`]s.
+#[derive(Debug, Clone)]
+pub struct PackedMoveTable {
+    moves: [[PackedMove; 4]; 6],
+}
+
+impl Default for PackedMoveTable {
+    fn default() -> PackedMoveTable {
+        PackedMoveTable::new()
+    }
+}
+
+impl PackedMoveTable {
+    pub fn new() -> PackedMoveTable {
+        PackedMoveTable {
+            moves: Face::ALL.map(|face| [0, 1, 2, 3].map(|count| PackedMove::new(face, count))),
+        }
+    }
+
+    pub fn get(&self, face: Face, count: u8) -> &PackedMove {
+        &self.moves[face.index()][usize::from(count % 4)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oorandom::Rand32;
+
+    #[test]
+    fn round_trip() {
+        assert_eq!(PackedCube::from_cube(&Cube::new_solved(3)), PackedCube::SOLVED);
+        assert!(PackedCube::SOLVED.to_cube().is_solved());
+
+        for seed in 0..256 {
+            let cube = Cube::new_random(3, seed);
+            assert_eq!(PackedCube::from_cube(&cube).to_cube(), cube);
+        }
+    }
+
+    #[test]
+    fn matches_cube_rotate() {
+        let table = PackedMoveTable::new();
+        let mut rand = Rand32::new(0);
+        for seed in 0..64 {
+            let mut cube = Cube::new_random(3, seed);
+            let mut packed = PackedCube::from_cube(&cube);
+            for _ in 0..100 {
+                let face = Face::from_index(rand.rand_range(0..6) as usize);
+                let count = rand.rand_range(0..4) as u8;
+                cube.rotate(face, 0..1, count);
+                packed.rotate_face(&table, face, count);
+                assert_eq!(packed.to_cube(), cube);
+            }
+        }
+    }
+
+    #[test]
+    fn sexy_move_order() {
+        let table = PackedMoveTable::new();
+        let mut packed = PackedCube::SOLVED;
+        for i in 1..=6 {
+            packed.rotate_face(&table, Face::R, 1);
+            packed.rotate_face(&table, Face::U, 1);
+            packed.rotate_face(&table, Face::R, 3);
+            packed.rotate_face(&table, Face::U, 3);
+            assert_eq!(packed.is_solved(), i == 6);
+        }
+    }
+}
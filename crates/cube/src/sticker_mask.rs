@@ -0,0 +1,187 @@
+use crate::{for_each_sticker, sticker_index, Cube, Face, Rgb};
+use alloc::{vec, vec::Vec};
+
+/// How a single sticker should be drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum StickerState {
+    /// Drawn in its normal color.
+    #[default]
+    Visible,
+    /// Drawn in [`StickerMask::GREY`], hiding its color.
+    Grey,
+    /// Drawn in its normal color, but emphasized.
+    Highlighted,
+}
+
+impl StickerState {
+    /// Returns the color to draw a sticker of color `color` with, leaving
+    /// highlighting up to the renderer.
+    pub fn color(self, color: Rgb) -> Rgb {
+        match self {
+            StickerState::Visible | StickerState::Highlighted => color,
+            StickerState::Grey => StickerMask::GREY,
+        }
+    }
+}
+
+/// A [`StickerState`] for every sticker of an n by n cube, for trainers that
+/// only want to show the stickers relevant to a step.
+///
+/// Stickers are addressed with the same coordinates as [`Cube::color_at`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StickerMask {
+    n: u16,
+    states: Vec<StickerState>,
+}
+
+impl StickerMask {
+    /// The color of greyed out stickers.
+    pub const GREY: Rgb = Rgb::new(128, 128, 128);
+
+    /// The color used to emphasize highlighted stickers.
+    pub const HIGHLIGHT: Rgb = Rgb::new(255, 0, 255);
+
+    /// Returns a mask with every sticker visible.
+    pub fn new(n: u16) -> StickerMask {
+        StickerMask::filled(n, StickerState::Visible)
+    }
+
+    pub fn filled(n: u16, state: StickerState) -> StickerMask {
+        let n_usize = usize::from(n);
+        StickerMask {
+            n,
+            states: vec![state; 6 * n_usize * n_usize],
+        }
+    }
+
+    pub fn n(&self) -> u16 {
+        self.n
+    }
+
+    pub fn get(&self, face: Face, x: i16, y: i16) -> StickerState {
+        self.states[sticker_index(self.n, face, x, y)]
+    }
+
+    pub fn set(&mut self, face: Face, x: i16, y: i16, state: StickerState) {
+        let index = sticker_index(self.n, face, x, y);
+        self.states[index] = state;
+    }
+
+    /// Returns whether a sticker is part of the U layer.
+    fn in_last_layer(n: u16, face: Face, y: i16) -> bool {
+        face == Face::U || (face != Face::D && y == (n / 2) as i16)
+    }
+
+    /// Shows the U layer and greys out everything else.
+    pub fn last_layer(n: u16) -> StickerMask {
+        let mut mask = StickerMask::filled(n, StickerState::Grey);
+        for_each_sticker(n, |face, x, y| {
+            if StickerMask::in_last_layer(n, face, y) {
+                mask.set(face, x, y, StickerState::Visible);
+            }
+        });
+        mask
+    }
+
+    /// Shows only the stickers of the U layer that have the U color, like an
+    /// OLL diagram.
+    pub fn oll(cube: &Cube) -> StickerMask {
+        let n = cube.n;
+        let mut mask = StickerMask::filled(n, StickerState::Grey);
+        for_each_sticker(n, |face, x, y| {
+            if StickerMask::in_last_layer(n, face, y) && cube.color_at(face, x, y) == Face::U {
+                mask.set(face, x, y, StickerState::Visible);
+            }
+        });
+        mask
+    }
+
+    /// Greys out the U layer and highlights the F2L slot between the side
+    /// faces `a` and `b`.
+    ///
+    /// The slot is made up of the outermost column of `a` and `b` next to
+    /// each other and the corner sticker on D between them.
+    pub fn f2l_slot(n: u16, a: Face, b: Face) -> StickerMask {
+        const SIDES: [Face; 4] = [Face::F, Face::R, Face::B, Face::L];
+        let position = |face| SIDES.iter().position(|&side| side == face);
+        let (Some(a_position), Some(b_position)) = (position(a), position(b)) else {
+            panic!("F2L slot faces must be side faces");
+        };
+        assert!(
+            a_position % 2 != b_position % 2,
+            "F2L slot faces must be adjacent"
+        );
+
+        let half_n = (n / 2) as i16;
+        // The column of `face` next to `other`, which comes either right
+        // after it or right before it in `SIDES`.
+        let column = |face, other| {
+            if (position(face).unwrap() + 1) % 4 == position(other).unwrap() {
+                half_n
+            } else {
+                -half_n
+            }
+        };
+        let d_x = if a == Face::R || b == Face::R { half_n } else { -half_n };
+        let d_y = if a == Face::F || b == Face::F { half_n } else { -half_n };
+
+        let mut mask = StickerMask::new(n);
+        for_each_sticker(n, |face, x, y| {
+            let state = if StickerMask::in_last_layer(n, face, y) {
+                StickerState::Grey
+            } else if (face == a && x == column(a, b))
+                || (face == b && x == column(b, a))
+                || (face == Face::D && x == d_x && y == d_y)
+            {
+                StickerState::Highlighted
+            } else {
+                StickerState::Visible
+            };
+            mask.set(face, x, y, state);
+        });
+        mask
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(mask: &StickerMask, state: StickerState) -> usize {
+        mask.states.iter().filter(|&&s| s == state).count()
+    }
+
+    #[test]
+    fn presets() {
+        let mask = StickerMask::last_layer(3);
+        assert_eq!(count(&mask, StickerState::Visible), 21);
+        assert_eq!(mask.get(Face::F, 1, 1), StickerState::Visible);
+        assert_eq!(mask.get(Face::F, 1, 0), StickerState::Grey);
+
+        let mask = StickerMask::last_layer(4);
+        assert_eq!(count(&mask, StickerState::Visible), 32);
+        assert_eq!(mask.get(Face::R, -2, 2), StickerState::Visible);
+        assert_eq!(mask.get(Face::R, -2, 1), StickerState::Grey);
+
+        let mut cube = Cube::new_solved(3);
+        assert_eq!(count(&StickerMask::oll(&cube), StickerState::Visible), 9);
+        cube.rotate_face(Face::R, 1);
+        let mask = StickerMask::oll(&cube);
+        assert_eq!(count(&mask, StickerState::Visible), 7);
+        assert_eq!(mask.get(Face::B, -1, 1), StickerState::Visible);
+
+        let mask = StickerMask::f2l_slot(3, Face::R, Face::F);
+        assert_eq!(mask, StickerMask::f2l_slot(3, Face::F, Face::R));
+        assert_eq!(count(&mask, StickerState::Grey), 21);
+        assert_eq!(count(&mask, StickerState::Highlighted), 5);
+        assert_eq!(mask.get(Face::F, 1, -1), StickerState::Highlighted);
+        assert_eq!(mask.get(Face::R, -1, 0), StickerState::Highlighted);
+        assert_eq!(mask.get(Face::D, 1, 1), StickerState::Highlighted);
+        assert_eq!(mask.get(Face::D, -1, 1), StickerState::Visible);
+
+        let mask = StickerMask::f2l_slot(3, Face::B, Face::L);
+        assert_eq!(mask.get(Face::B, 1, 0), StickerState::Highlighted);
+        assert_eq!(mask.get(Face::L, -1, 0), StickerState::Highlighted);
+        assert_eq!(mask.get(Face::D, -1, -1), StickerState::Highlighted);
+    }
+}
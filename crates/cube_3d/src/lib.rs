@@ -1,4 +1,4 @@
-use cube::{Cube, Face};
+use cube::{ColorScheme, Cube, Face, StickerMask, StickerState};
 use glam::{vec2, vec4, Vec3, Vec4};
 
 pub const VERTICES_PER_FACELET: usize = 4;
@@ -9,9 +9,19 @@ pub fn num_facelets(n: u16) -> usize {
     usize::from(n) * usize::from(n) * 6
 }
 
-pub fn update_facelet_colors(cube: &Cube, colors: &mut [Vec4]) {
+/// Fills in the vertex colors of every facelet.
+///
+/// Greyed out stickers in `mask` are drawn grey, and highlighted ones are
+/// tinted with [`StickerMask::HIGHLIGHT`].
+pub fn update_facelet_colors(
+    cube: &Cube,
+    color_scheme: &ColorScheme,
+    mask: Option<&StickerMask>,
+    colors: &mut [Vec4],
+) {
     let n = cube.n;
     debug_assert_eq!(colors.len(), num_facelets(n) * VERTICES_PER_FACELET);
+    debug_assert!(mask.is_none_or(|mask| mask.n() == n));
 
     let half_n = (n / 2) as i16;
     let mut vertex_index = 0;
@@ -24,15 +34,13 @@ pub fn update_facelet_colors(cube: &Cube, colors: &mut [Vec4]) {
                 if x == 0 && n % 2 == 0 {
                     continue;
                 }
-                let color = cube.color_at(face, x, y);
-                let color = match color {
-                    Face::U => vec4(1.0, 1.0, 1.0, 1.0),
-                    Face::L => vec4(1.0, 0.5, 0.0, 1.0),
-                    Face::F => vec4(0.0, 1.0, 0.0, 1.0),
-                    Face::R => vec4(1.0, 0.0, 0.0, 1.0),
-                    Face::B => vec4(0.0, 0.0, 1.0, 1.0),
-                    Face::D => vec4(1.0, 1.0, 0.0, 1.0),
-                };
+                let state = mask.map_or(StickerState::Visible, |mask| mask.get(face, x, y));
+                let [r, g, b] = state.color(color_scheme.color(cube.color_at(face, x, y))).to_f32();
+                let mut color = vec4(r, g, b, 1.0);
+                if state == StickerState::Highlighted {
+                    let [r, g, b] = StickerMask::HIGHLIGHT.to_f32();
+                    color = color.lerp(vec4(r, g, b, 1.0), 0.5);
+                }
                 colors[vertex_index..vertex_index + 4].fill(color);
                 vertex_index += 4;
             }
@@ -1,5 +1,5 @@
 use bytemuck::{bytes_of, cast_slice, cast_slice_mut};
-use cube::{Cube, EdgeSticker, Face, RotatedCube};
+use cube::{ColorScheme, Cube, EdgeSticker, Face, RotatedCube};
 use cube_3d::{set_up_facelets, update_facelet_colors};
 use glam::{vec3, EulerRot, Mat4, Quat, Vec3, Vec4};
 use std::{f32::consts::PI, mem::size_of};
@@ -135,8 +135,11 @@ async fn run(event_loop: EventLoop<()>, window: Window) {
     let mut cube = Cube::new_random(n, 0);
     let mut cube = RotatedCube::new(&mut cube);
     let mut layers = 2;
+    let color_scheme = ColorScheme::default();
     update_facelet_colors(
         cube.cube,
+        &color_scheme,
+        None,
         cast_slice_mut(&mut color_buffer.slice(..).get_mapped_range_mut()),
     );
 
@@ -312,7 +315,7 @@ async fn run(event_loop: EventLoop<()>, window: Window) {
                             queue.write_buffer(&uniform_buffer, 0, bytes_of(&mvp));
                             let mut buf =
                                 vec![Vec4::ZERO; num_facelets * cube_3d::VERTICES_PER_FACELET];
-                            update_facelet_colors(cube.cube, &mut buf);
+                            update_facelet_colors(cube.cube, &color_scheme, None, &mut buf);
                             queue.write_buffer(&color_buffer, 0, cast_slice(&buf));
 
                             let frame = surface
@@ -0,0 +1,336 @@
+//! This crate converts the state of a [`Cube`] to and from the KPuzzle
+//! pattern JSON used by cubing.js, which maps the name of every orbit to the
+//! pieces in it and their orientations.
+//!
+//! `CORNERS`, `EDGES` and `CENTERS` follow the 3x3x3 definition of cubing.js,
+//! so patterns for 2x2x2 and 3x3x3 cubes can be exchanged as is. Bigger cubes
+//! add one orbit per [`CubeLayer`](cube::CubeLayer) component, with pieces in
+//! the order of this crate:
+//!
+//! - `WINGS`, `WINGS2` and so on for the [`Wings`](cube::Wings) of each layer,
+//!   counted from the outside.
+//! - `CENTERS2`, `CENTERS3` and so on for the [`XCenters`](cube::XCenters),
+//!   [`TCenters`](cube::TCenters) and left and right
+//!   [`Obliques`](cube::Obliques) of each layer, in that order. On even cubes,
+//!   which have no fixed centers, the first of these is called `CENTERS`.
+
+use cube::{CornerOrientation, CornerSticker, Cube, EdgeOrientation, EdgeSticker, Face};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[cfg(test)]
+mod tests;
+
+/// The pieces in an orbit and their orientations.
+///
+/// Position `i` holds piece `pieces[i]`, with facelet `k` of the position
+/// showing facelet `(k + orientation[i]) % n` of the piece.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrbitPattern {
+    pub pieces: Vec<u8>,
+    pub orientation: Vec<u8>,
+}
+
+/// A KPuzzle pattern, indexed by orbit name.
+pub type KPattern = BTreeMap<String, OrbitPattern>;
+
+/// The corners in cubing.js order, with their facelets in order.
+const CORNERS: [[Face; 3]; 8] = {
+    use Face::*;
+    [
+        [U, F, R],
+        [U, R, B],
+        [U, B, L],
+        [U, L, F],
+        [D, R, F],
+        [D, F, L],
+        [D, L, B],
+        [D, B, R],
+    ]
+};
+
+/// The edges in cubing.js order, with their facelets in order.
+const EDGES: [[Face; 2]; 12] = {
+    use Face::*;
+    [
+        [U, F],
+        [U, R],
+        [U, B],
+        [U, L],
+        [D, F],
+        [D, R],
+        [D, B],
+        [D, L],
+        [F, R],
+        [F, L],
+        [B, R],
+        [B, L],
+    ]
+};
+
+fn corner_facelet(piece: usize, facelet: usize) -> CornerSticker {
+    let faces = CORNERS[piece];
+    CornerSticker::from_faces(
+        faces[facelet],
+        faces[(facelet + 1) % 3],
+        faces[(facelet + 2) % 3],
+    )
+}
+
+fn edge_facelet(piece: usize, facelet: usize) -> EdgeSticker {
+    let faces = EDGES[piece];
+    EdgeSticker::from_faces(faces[facelet], faces[(facelet + 1) % 2])
+}
+
+/// Returns the piece and facelet of a corner sticker.
+fn find_corner(sticker: CornerSticker) -> (u8, u8) {
+    for piece in 0..8 {
+        for facelet in 0..3 {
+            if corner_facelet(piece, facelet) == sticker {
+                return (piece as u8, facelet as u8);
+            }
+        }
+    }
+    unreachable!()
+}
+
+/// Returns the piece and facelet of an edge sticker.
+fn find_edge(sticker: EdgeSticker) -> (u8, u8) {
+    for piece in 0..12 {
+        for facelet in 0..2 {
+            if edge_facelet(piece, facelet) == sticker {
+                return (piece as u8, facelet as u8);
+            }
+        }
+    }
+    unreachable!()
+}
+
+/// Returns the name of the `index`th center orbit, counting from zero.
+fn centers_name(index: usize) -> String {
+    match index {
+        0 => String::from("CENTERS"),
+        _ => format!("CENTERS{}", index + 1),
+    }
+}
+
+/// Returns the name of the wing orbit of `layer`.
+fn wings_name(layer: usize) -> String {
+    match layer {
+        0 => String::from("WINGS"),
+        _ => format!("WINGS{}", layer + 1),
+    }
+}
+
+/// Returns an orbit of single-facelet pieces, numbered by index.
+fn stickers_orbit(stickers: impl Iterator<Item = usize>) -> OrbitPattern {
+    let pieces = stickers.map(|index| index as u8).collect::<Vec<_>>();
+    OrbitPattern {
+        orientation: vec![0; pieces.len()],
+        pieces,
+    }
+}
+
+/// Returns the state of `cube` as a KPuzzle pattern.
+pub fn to_kpattern(cube: &Cube) -> Result<KPattern, &'static str> {
+    if cube.n < 2 {
+        return Err("cube too small");
+    }
+    let mut pattern = KPattern::new();
+
+    let mut corners = OrbitPattern {
+        pieces: Vec::new(),
+        orientation: Vec::new(),
+    };
+    for position in 0..8 {
+        let (piece, facelet) = find_corner(cube.corners.at(corner_facelet(position, 0)));
+        corners.pieces.push(piece);
+        corners.orientation.push(facelet);
+    }
+    pattern.insert(String::from("CORNERS"), corners);
+
+    let mut centers = 0;
+    if cube.n % 2 == 1 {
+        let mut edges = OrbitPattern {
+            pieces: Vec::new(),
+            orientation: Vec::new(),
+        };
+        for position in 0..12 {
+            let (piece, facelet) = find_edge(cube.edges.at(edge_facelet(position, 0)));
+            edges.pieces.push(piece);
+            edges.orientation.push(facelet);
+        }
+        pattern.insert(String::from("EDGES"), edges);
+
+        // Centers never move, since every move keeps them fixed.
+        pattern.insert(centers_name(centers), stickers_orbit(0..6));
+        centers += 1;
+    }
+
+    for (i, layer) in cube.layers.iter().enumerate() {
+        let wings = layer
+            .wings
+            .permutation
+            .iter()
+            .map(|sticker| sticker.index());
+        pattern.insert(wings_name(i), stickers_orbit(wings));
+
+        let xcenters = layer
+            .xcenters
+            .permutation
+            .iter()
+            .map(|sticker| sticker.index());
+        pattern.insert(centers_name(centers), stickers_orbit(xcenters));
+        centers += 1;
+
+        if cube.n % 2 == 1 {
+            let tcenters = layer
+                .tcenters
+                .permutation
+                .iter()
+                .map(|sticker| sticker.index());
+            pattern.insert(centers_name(centers), stickers_orbit(tcenters));
+            centers += 1;
+        }
+
+        for pair in &layer.obliques {
+            for obliques in [&pair.left, &pair.right] {
+                let obliques = obliques.permutation.iter().map(|sticker| sticker.index());
+                pattern.insert(centers_name(centers), stickers_orbit(obliques));
+                centers += 1;
+            }
+        }
+    }
+
+    Ok(pattern)
+}
+
+/// Looks up an orbit and checks that it is a valid arrangement of `len`
+/// pieces with `orientations` orientations each.
+fn orbit<'a>(
+    pattern: &'a KPattern,
+    name: &str,
+    len: usize,
+    orientations: u8,
+) -> Result<&'a OrbitPattern, &'static str> {
+    let orbit = pattern.get(name).ok_or("missing orbit")?;
+    if orbit.pieces.len() != len || orbit.orientation.len() != len {
+        return Err("wrong number of pieces in orbit");
+    }
+    let mut seen = vec![false; len];
+    for &piece in &orbit.pieces {
+        let seen = seen
+            .get_mut(usize::from(piece))
+            .ok_or("piece out of range")?;
+        if *seen {
+            return Err("piece appears twice in orbit");
+        }
+        *seen = true;
+    }
+    if orbit.orientation.iter().any(|&o| o >= orientations) {
+        return Err("orientation out of range");
+    }
+    Ok(orbit)
+}
+
+/// Reads an orbit of single-facelet pieces into a permutation of stickers.
+fn read_stickers<T>(
+    pattern: &KPattern,
+    name: &str,
+    from_index: impl Fn(usize) -> T,
+) -> Result<[T; 24], &'static str> {
+    let orbit = orbit(pattern, name, 24, 1)?;
+    Ok(std::array::from_fn(|i| {
+        from_index(usize::from(orbit.pieces[i]))
+    }))
+}
+
+/// Builds an `n` by `n` cube from a KPuzzle pattern.
+///
+/// Patterns with moved centers on odd cubes are rejected, since [`Cube`]
+/// keeps its centers fixed.
+pub fn from_kpattern(n: u16, pattern: &KPattern) -> Result<Cube, &'static str> {
+    if n < 2 {
+        return Err("cube too small");
+    }
+    let mut cube = Cube::new_solved(n);
+
+    let corners = orbit(pattern, "CORNERS", 8, 3)?;
+    for position in 0..8 {
+        let piece = usize::from(corners.pieces[position]);
+        let orientation = usize::from(corners.orientation[position]);
+        for facelet in 0..3 {
+            let sticker = corner_facelet(position, facelet);
+            if sticker.orientation() == CornerOrientation::Good {
+                let home = corner_facelet(piece, (facelet + orientation) % 3);
+                let slot = sticker.permutation().index();
+                cube.corners.permutation[slot] = home.permutation();
+                cube.corners.orientation[slot] = home.orientation();
+            }
+        }
+    }
+
+    let mut centers = 0;
+    if n % 2 == 1 {
+        let edges = orbit(pattern, "EDGES", 12, 2)?;
+        for position in 0..12 {
+            let piece = usize::from(edges.pieces[position]);
+            let orientation = usize::from(edges.orientation[position]);
+            for facelet in 0..2 {
+                let sticker = edge_facelet(position, facelet);
+                if sticker.orientation() == EdgeOrientation::Good {
+                    let home = edge_facelet(piece, (facelet + orientation) % 2);
+                    let slot = sticker.permutation().index();
+                    cube.edges.permutation[slot] = home.permutation();
+                    cube.edges.orientation[slot] = home.orientation();
+                }
+            }
+        }
+
+        let fixed = orbit(pattern, &centers_name(centers), 6, 4)?;
+        if fixed
+            .pieces
+            .iter()
+            .enumerate()
+            .any(|(i, &piece)| usize::from(piece) != i)
+        {
+            return Err("centers must not move");
+        }
+        centers += 1;
+    }
+
+    for (i, layer) in cube.layers.iter_mut().enumerate() {
+        layer.wings.permutation = read_stickers(pattern, &wings_name(i), EdgeSticker::from_index)?;
+
+        layer.xcenters.permutation =
+            read_stickers(pattern, &centers_name(centers), CornerSticker::from_index)?;
+        centers += 1;
+
+        if n % 2 == 1 {
+            layer.tcenters.permutation =
+                read_stickers(pattern, &centers_name(centers), EdgeSticker::from_index)?;
+            centers += 1;
+        }
+
+        for pair in &mut layer.obliques {
+            for obliques in [&mut pair.left, &mut pair.right] {
+                obliques.permutation =
+                    read_stickers(pattern, &centers_name(centers), EdgeSticker::from_index)?;
+                centers += 1;
+            }
+        }
+    }
+
+    Ok(cube)
+}
+
+/// Returns the state of `cube` as KPuzzle pattern JSON.
+pub fn to_json(cube: &Cube) -> Result<String, &'static str> {
+    Ok(serde_json::to_string(&to_kpattern(cube)?).unwrap())
+}
+
+/// Parses KPuzzle pattern JSON for an `n` by `n` cube.
+pub fn from_json(n: u16, json: &str) -> Result<Cube, &'static str> {
+    let pattern = serde_json::from_str::<KPattern>(json).map_err(|_| "invalid pattern JSON")?;
+    from_kpattern(n, &pattern)
+}
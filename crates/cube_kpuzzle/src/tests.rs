@@ -0,0 +1,93 @@
+use super::*;
+
+#[test]
+fn round_trip() {
+    for n in 2..=7 {
+        for seed in 0..16 {
+            let mut cube = Cube::new_random(n, seed);
+            if n % 2 == 0 {
+                // Even cubes have no edges or t-centers, so leave them solved.
+                cube.edges = cube::Edges::new();
+                for layer in &mut cube.layers {
+                    layer.tcenters = cube::TCenters::new();
+                }
+            }
+            assert_eq!(from_json(n, &to_json(&cube).unwrap()), Ok(cube));
+        }
+    }
+}
+
+#[test]
+fn matches_cubing_js() {
+    let mut cube = Cube::new_solved(3);
+    cube.rotate_face(Face::R, 1);
+    let pattern = to_kpattern(&cube).unwrap();
+    assert_eq!(pattern["CORNERS"].pieces, [4, 0, 2, 3, 7, 5, 6, 1]);
+    assert_eq!(pattern["CORNERS"].orientation, [2, 1, 0, 0, 1, 0, 0, 2]);
+    assert_eq!(
+        pattern["EDGES"].pieces,
+        [0, 8, 2, 3, 4, 10, 6, 7, 5, 9, 1, 11]
+    );
+    assert_eq!(pattern["EDGES"].orientation, [0; 12]);
+
+    let mut cube = Cube::new_solved(3);
+    cube.rotate_face(Face::F, 1);
+    let pattern = to_kpattern(&cube).unwrap();
+    assert_eq!(
+        pattern["EDGES"].pieces,
+        [9, 1, 2, 3, 8, 5, 6, 7, 0, 4, 10, 11]
+    );
+    assert_eq!(
+        pattern["EDGES"].orientation,
+        [1, 0, 0, 0, 1, 0, 0, 0, 1, 1, 0, 0]
+    );
+}
+
+#[test]
+fn orbits() {
+    for (n, expected) in [
+        (2, &["CORNERS"][..]),
+        (3, &["CENTERS", "CORNERS", "EDGES"]),
+        (4, &["CENTERS", "CORNERS", "WINGS"]),
+        (
+            5,
+            &[
+                "CENTERS", "CENTERS2", "CENTERS3", "CORNERS", "EDGES", "WINGS",
+            ],
+        ),
+        (
+            6,
+            &[
+                "CENTERS", "CENTERS2", "CENTERS3", "CENTERS4", "CORNERS", "WINGS", "WINGS2",
+            ],
+        ),
+        (
+            7,
+            &[
+                "CENTERS", "CENTERS2", "CENTERS3", "CENTERS4", "CENTERS5", "CENTERS6", "CENTERS7",
+                "CORNERS", "EDGES", "WINGS", "WINGS2",
+            ],
+        ),
+    ] {
+        let pattern = to_kpattern(&Cube::new_solved(n)).unwrap();
+        assert_eq!(pattern.keys().collect::<Vec<_>>(), expected, "{n}x{n}x{n}");
+    }
+}
+
+#[test]
+fn errors() {
+    let json = to_json(&Cube::new_solved(2)).unwrap();
+    assert_eq!(
+        json,
+        r#"{"CORNERS":{"pieces":[0,1,2,3,4,5,6,7],"orientation":[0,0,0,0,0,0,0,0]}}"#
+    );
+    assert_eq!(from_json(3, &json), Err("missing orbit"));
+    assert_eq!(from_json(2, "{"), Err("invalid pattern JSON"));
+    assert_eq!(
+        from_json(2, &json.replace("[0,1,2,3,4,5,6,7]", "[0,1,2,3,4,5,7,7]")),
+        Err("piece appears twice in orbit")
+    );
+    assert_eq!(from_json(5, &json), Err("missing orbit"));
+    assert_eq!(to_json(&Cube::new_solved(1)), Err("cube too small"));
+    assert_eq!(from_json(1, &json), Err("cube too small"));
+}
@@ -0,0 +1,524 @@
+use crate::{Delimiter, Move, Tree};
+use alloc::{boxed::Box, vec, vec::Vec};
+use cube::{cube::RotatedCube, Axis, Cube, Face};
+
+/// The quarter turns of a run of moves about one axis, for every layer
+/// counted from the R, U or F side.
+pub(crate) type AxisRun = (Axis, Vec<u8>);
+
+pub(crate) fn axis_run(mv: Move) -> AxisRun {
+    let (axis, flipped) = match mv.face {
+        Face::R => (Axis::X, false),
+        Face::L => (Axis::X, true),
+        Face::U => (Axis::Y, false),
+        Face::D => (Axis::Y, true),
+        Face::F => (Axis::Z, false),
+        Face::B => (Axis::Z, true),
+    };
+    let mut layers = vec![0; usize::from(mv.n)];
+    for layer in mv.start..mv.end {
+        if flipped {
+            layers[usize::from(mv.n - 1 - layer)] = (4 - mv.count % 4) % 4;
+        } else {
+            layers[usize::from(layer)] = mv.count % 4;
+        }
+    }
+    (axis, layers)
+}
+
+/// Returns a normal form of `moves`, which is the same for move sequences
+/// that only differ in cancellations and in the order of moves about the
+/// same axis.
+fn normal_form(moves: impl IntoIterator<Item = Move>) -> Vec<AxisRun> {
+    let mut runs: Vec<AxisRun> = Vec::new();
+    for mv in moves {
+        let (axis, layers) = axis_run(mv);
+        match runs.last_mut() {
+            Some((last_axis, last)) if *last_axis == axis => {
+                for (a, b) in last.iter_mut().zip(layers) {
+                    *a = (*a + b) % 4;
+                }
+                if last.iter().all(|&count| count == 0) {
+                    runs.pop();
+                }
+            }
+            _ => {
+                if layers.iter().any(|&count| count != 0) {
+                    runs.push((axis, layers));
+                }
+            }
+        }
+    }
+    runs
+}
+
+/// Merges adjacent moves of the same layers and drops moves that cancel.
+pub(crate) fn simplify(moves: impl IntoIterator<Item = Move>) -> Vec<Move> {
+    let mut result: Vec<Move> = Vec::new();
+    for mv in moves {
+        match result.last_mut() {
+            Some(last) if (last.face, last.start, last.end) == (mv.face, mv.start, mv.end) => {
+                last.count = (last.count + mv.count) % 4;
+                if last.count == 0 {
+                    result.pop();
+                }
+            }
+            _ => {
+                if mv.count % 4 != 0 {
+                    result.push(mv);
+                }
+            }
+        }
+    }
+    result
+}
+
+fn inverse(moves: &[Move]) -> impl Iterator<Item = Move> + '_ {
+    moves.iter().rev().map(|mv| mv.inverse())
+}
+
+/// A position in a move sequence: after the moves before `index` and
+/// `count` quarter turns of the move at `index`, which lets a part of an
+/// alg end in the middle of a move that was merged with the next part.
+#[derive(Clone, Copy)]
+struct Cut {
+    index: usize,
+    count: u8,
+}
+
+fn cuts(moves: &[Move]) -> Vec<Cut> {
+    let mut result = Vec::new();
+    for (index, mv) in moves.iter().enumerate() {
+        result.push(Cut { index, count: 0 });
+        for count in 1..4 {
+            if count != mv.count % 4 {
+                result.push(Cut { index, count });
+            }
+        }
+    }
+    result.push(Cut {
+        index: moves.len(),
+        count: 0,
+    });
+    result
+}
+
+/// Returns the moves between two cuts, where `from` comes before `to`.
+fn between(moves: &[Move], from: Cut, to: Cut) -> Vec<Move> {
+    let with_count = |mv: Move, count: u8| Move { count, ..mv };
+    let mut result = Vec::new();
+    if from.index == to.index {
+        let count = (to.count + 4 - from.count) % 4;
+        if count != 0 {
+            result.push(with_count(moves[from.index], count));
+        }
+        return result;
+    }
+    let first = moves[from.index];
+    let count = (first.count + 4 - from.count) % 4;
+    if count != 0 {
+        result.push(with_count(first, count));
+    }
+    result.extend_from_slice(&moves[from.index + 1..to.index]);
+    if to.count != 0 {
+        result.push(with_count(moves[to.index], to.count));
+    }
+    result
+}
+
+/// Returns the number of moves between two cuts, like the length of
+/// [`between`].
+fn len_between(moves: &[Move], from: Cut, to: Cut) -> usize {
+    if from.index == to.index {
+        return usize::from(to.count != from.count);
+    }
+    usize::from(moves[from.index].count % 4 != from.count)
+        + (to.index - from.index - 1)
+        + usize::from(to.count != 0)
+}
+
+/// Returns whether the number of transpositions in `permutation` is odd.
+fn is_odd(permutation: &[usize]) -> bool {
+    let mut seen = vec![false; permutation.len()];
+    let mut cycles = 0;
+    for start in 0..permutation.len() {
+        if !seen[start] {
+            cycles += 1;
+            let mut i = start;
+            while !seen[i] {
+                seen[i] = true;
+                i = permutation[i];
+            }
+        }
+    }
+    (permutation.len() - cycles) % 2 == 1
+}
+
+/// Returns whether `moves` can be a commutator at all, which quickly rules
+/// out most algs. A commutator turns every layer a whole number of times in
+/// total, and moves the pieces of every orbit by an even permutation.
+fn may_be_commutator(moves: &[Move]) -> bool {
+    let Some(n) = moves.first().map(|mv| mv.n) else {
+        return false;
+    };
+
+    let mut turns = vec![0; 3 * usize::from(n)];
+    for &mv in moves {
+        let (axis, layers) = axis_run(mv);
+        let offset = axis as usize * usize::from(n);
+        for (total, count) in turns[offset..].iter_mut().zip(layers) {
+            *total = (*total + count) % 4;
+        }
+    }
+    if turns.iter().any(|&total| total != 0) {
+        return false;
+    }
+
+    let mut cube = Cube::new_solved(n);
+    let mut rotated = RotatedCube::new(&mut cube);
+    for mv in moves {
+        rotated.rotate(mv.face, mv.start..mv.end, mv.count);
+    }
+    let mut orbits = vec![
+        cube.corners.permutation.map(|piece| piece.index()).to_vec(),
+        cube.edges.permutation.map(|piece| piece.index()).to_vec(),
+    ];
+    for layer in &cube.layers {
+        orbits.push(
+            layer
+                .wings
+                .permutation
+                .map(|sticker| sticker.index())
+                .to_vec(),
+        );
+        orbits.push(
+            layer
+                .tcenters
+                .permutation
+                .map(|sticker| sticker.index())
+                .to_vec(),
+        );
+        orbits.push(
+            layer
+                .xcenters
+                .permutation
+                .map(|sticker| sticker.index())
+                .to_vec(),
+        );
+        for pair in &layer.obliques {
+            orbits.push(
+                pair.left
+                    .permutation
+                    .map(|sticker| sticker.index())
+                    .to_vec(),
+            );
+            orbits.push(
+                pair.right
+                    .permutation
+                    .map(|sticker| sticker.index())
+                    .to_vec(),
+            );
+        }
+    }
+    !orbits.iter().any(|orbit| is_odd(orbit))
+}
+
+fn group(delim: Delimiter, moves: &[Move]) -> Box<Tree> {
+    Box::new(Tree::Group(
+        delim,
+        moves.iter().copied().map(Tree::Move).collect(),
+    ))
+}
+
+/// The length of the longest alg [`find_commutator`] looks at, since the
+/// time of the search grows with the fourth power of the length.
+const MAX_COMMUTATOR_LEN: usize = 32;
+
+/// The stickers of an `n` by `n` cube, which tell quickly whether two move
+/// sequences can have the same normal form: if they do, they move every
+/// sticker to the same place.
+struct Stickers {
+    n: usize,
+    /// For every axis and every layer counted from the R, U or F side, the
+    /// place a quarter turn of the layer moves each sticker to.
+    turns: Vec<Vec<u16>>,
+}
+
+impl Stickers {
+    fn new(n: u16) -> Stickers {
+        let n = i32::from(n);
+        // Stickers are at doubled coordinates, on the faces at -n and n.
+        let index = |p: [i32; 3]| {
+            let axis = (0..3).find(|&axis| p[axis].abs() == n).unwrap();
+            let [u, v] = [(axis + 1) % 3, (axis + 2) % 3].map(|i| (p[i] + n - 1) / 2);
+            let face = 2 * axis + usize::from(p[axis] < 0);
+            (face * n as usize + u as usize) * n as usize + v as usize
+        };
+        let mut stickers = vec![[0; 3]; 6 * (n * n) as usize];
+        for axis in 0..3 {
+            for side in [n, -n] {
+                for u in 0..n {
+                    for v in 0..n {
+                        let mut p = [0; 3];
+                        p[axis] = side;
+                        p[(axis + 1) % 3] = 2 * u - (n - 1);
+                        p[(axis + 2) % 3] = 2 * v - (n - 1);
+                        stickers[index(p)] = p;
+                    }
+                }
+            }
+        }
+
+        let mut turns = Vec::new();
+        for axis in 0..3 {
+            for layer in 0..n {
+                let turn = stickers.iter().map(|&p| {
+                    let piece = p[axis].clamp(-(n - 1), n - 1);
+                    if piece != n - 1 - 2 * layer {
+                        return index(p) as u16;
+                    }
+                    let [x, y, z] = p;
+                    index(match axis {
+                        0 => [x, z, -y],
+                        1 => [-z, y, x],
+                        _ => [y, -x, z],
+                    }) as u16
+                });
+                turns.push(turn.collect());
+            }
+        }
+        Stickers {
+            n: n as usize,
+            turns,
+        }
+    }
+
+    /// Returns the places of the stickers after the moves leading to
+    /// `places`, followed by `mv`.
+    fn apply(&self, places: &[u16], mv: Move) -> Vec<u16> {
+        let (axis, layers) = axis_run(mv);
+        let mut places = places.to_vec();
+        for (layer, count) in layers.into_iter().enumerate() {
+            let turn = &self.turns[axis as usize * self.n + layer];
+            for _ in 0..count {
+                for place in &mut places {
+                    *place = turn[usize::from(*place)];
+                }
+            }
+        }
+        places
+    }
+}
+
+/// The length of a decomposition and the number of moves it splits.
+type Score = (usize, usize);
+
+/// Finds the shortest way to write `moves` as a commutator `[B, C]` or a
+/// conjugated commutator `[A: [B, C]]`, counting the moves of `A`, `B` and
+/// `C`.
+///
+/// Moves may cancel between the parts, like in `[R: [U, R D R']]`, which is
+/// `R U R D R' U' R D' R2`. Returns `None` if `moves` is not a commutator,
+/// or if it has more than 32 moves, which would take too long to search.
+pub fn find_commutator(moves: &[Move]) -> Option<Tree> {
+    let moves = simplify(moves.iter().copied());
+    if moves.len() > MAX_COMMUTATOR_LEN || !may_be_commutator(&moves) {
+        return None;
+    }
+    let start = Cut { index: 0, count: 0 };
+    let stickers = Stickers::new(moves[0].n);
+
+    // Among equally long decompositions, the one splitting the fewest moves
+    // wins, so `[R: [U, R D R']]` is preferred over `[R2: [R' U R, D]]`.
+    let mut best: Option<(Score, [Vec<Move>; 3])> = None;
+    for setup_end in cuts(&moves) {
+        let setup = between(&moves, start, setup_end);
+        if best
+            .as_ref()
+            .is_some_and(|best| setup.len() + 2 > best.0 .0)
+        {
+            continue;
+        }
+
+        let inner = simplify(
+            inverse(&setup)
+                .chain(moves.iter().copied())
+                .chain(setup.iter().copied()),
+        );
+        let target = normal_form(inner.iter().copied());
+
+        // Candidates are kept as cuts and lengths, so only the ones that are
+        // checked are cut out of `inner`.
+        let inner_cuts = cuts(&inner);
+
+        // The places of the stickers after the moves up to every cut, and
+        // where the stickers there come from.
+        let mut after_moves = vec![(0..stickers.turns[0].len() as u16).collect::<Vec<_>>()];
+        for &mv in &inner {
+            after_moves.push(stickers.apply(after_moves.last().unwrap(), mv));
+        }
+        let places = inner_cuts
+            .iter()
+            .map(|cut| match cut.count {
+                0 => after_moves[cut.index].clone(),
+                count => stickers.apply(
+                    &after_moves[cut.index],
+                    Move {
+                        count,
+                        ..inner[cut.index]
+                    },
+                ),
+            })
+            .collect::<Vec<_>>();
+        let origins = places
+            .iter()
+            .map(|places| {
+                let mut origins = vec![0; places.len()];
+                for (sticker, &place) in places.iter().enumerate() {
+                    origins[usize::from(place)] = sticker as u16;
+                }
+                origins
+            })
+            .collect::<Vec<_>>();
+        let inner_places = places.last().unwrap();
+        // Whether `[B, C]` moves the stickers like `inner`, where `B` ends at
+        // the `b`th cut and `C` at the `c`th.
+        let same_places = |b: usize, c: usize| {
+            (0..inner_places.len()).all(|sticker| {
+                let place = origins[b][usize::from(places[c][sticker])];
+                places[b][usize::from(origins[c][usize::from(place)])] == inner_places[sticker]
+            })
+        };
+        let mut candidates = Vec::new();
+        for (i, &b_end) in inner_cuts.iter().enumerate() {
+            let b_len = len_between(&inner, start, b_end);
+            if b_len == 0 {
+                continue;
+            }
+            for (j, &c_end) in inner_cuts.iter().enumerate().skip(i + 1) {
+                let c_len = len_between(&inner, b_end, c_end);
+                if c_len != 0 {
+                    let splits = usize::from(setup_end.count != 0)
+                        + usize::from(b_end.count != 0)
+                        + usize::from(c_end.count != 0);
+                    candidates.push((b_len + c_len, splits, b_len, i, j));
+                }
+            }
+        }
+        candidates.sort_by_key(|&(len, splits, b_len, ..)| (len, splits, b_len));
+
+        for (len, splits, _, i, j) in candidates {
+            let score = (setup.len() + len, splits);
+            if best.as_ref().is_some_and(|best| score >= best.0) {
+                break;
+            }
+            if !same_places(i, j) {
+                continue;
+            }
+            let b = between(&inner, start, inner_cuts[i]);
+            let c = between(&inner, inner_cuts[i], inner_cuts[j]);
+            let commutator = b
+                .iter()
+                .copied()
+                .chain(c.iter().copied())
+                .chain(inverse(&b))
+                .chain(inverse(&c));
+            if normal_form(commutator) == target {
+                best = Some((score, [setup.clone(), b, c]));
+                break;
+            }
+        }
+    }
+
+    let (_, [setup, b, c]) = best?;
+    let commutator = Tree::Comm(
+        Delimiter::Brackets,
+        group(Delimiter::None, &b),
+        group(Delimiter::Brackets, &c),
+    );
+    if setup.is_empty() {
+        return Some(commutator);
+    }
+    Some(Tree::Conj(
+        Delimiter::Brackets,
+        group(Delimiter::None, &setup),
+        Box::new(Tree::Group(Delimiter::Brackets, vec![commutator])),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_alg, ParseMode};
+    use alloc::{format, string::String};
+    use expect_test::{expect, Expect};
+
+    #[test]
+    fn commutators() {
+        #[track_caller]
+        fn check(n: u16, alg: &str, expected: Expect) {
+            let moves = parse_alg(n, ParseMode::Wca, alg).unwrap().to_moves();
+            let actual = match find_commutator(&moves) {
+                Some(tree) => {
+                    let reparsed = parse_alg(n, ParseMode::Wca, &format!("{tree}")).unwrap();
+                    assert_eq!(reparsed, Tree::Group(Delimiter::None, vec![tree.clone()]));
+                    assert_eq!(
+                        normal_form(tree.to_moves()),
+                        normal_form(moves.iter().copied())
+                    );
+                    format!("{tree}")
+                }
+                None => String::from("not found"),
+            };
+            expected.assert_eq(&actual);
+        }
+
+        check(3, "R U R' D R U' R' D'", expect!["[R U R', D]"]);
+        check(3, "R U R D R' U' R D' R2", expect!["[R: [U, R D R']]"]);
+        check(3, "U' R' D' R U R' D R", expect!["[U', R' D' R]"]);
+        check(3, "M' U2 M U2", expect!["[M', U2]"]);
+        check(3, "D R U R' D' R U' R'", expect!["[D, R U R']"]);
+        check(3, "R2 U R U R' U' R' U' R' U R'", expect!["not found"]);
+        check(
+            3,
+            "R U R' U' R' F R2 U' R' U' R U R' F'",
+            expect!["not found"],
+        );
+        check(3, "U R' D R U2 R' D' R U", expect!["[U R' D R, U2]"]);
+        check(5, "Rw U' r' U Rw' U' r U", expect!["[Rw, U' r' U]"]);
+        check(3, "[R U R', D] [F: [R U2 R', D']]", expect!["not found"]);
+        check(
+            3,
+            "[R' F R2 U' R' U' R U R' F' R U R' U']2",
+            expect!["not found"],
+        );
+        check(
+            7,
+            "[3Rw' U: [3r2 u' 3Lw', D2 Bw' r F2]] [Fw: [3l' D2, 3Rw' u]]",
+            expect!["not found"],
+        );
+    }
+
+    #[test]
+    fn stickers() {
+        for n in 2..=7 {
+            let stickers = Stickers::new(n);
+            let solved = (0..6 * n * n).collect::<Vec<_>>();
+            let places = |alg: &str| {
+                let moves = parse_alg(n, ParseMode::Wca, alg).unwrap().to_moves();
+                moves
+                    .into_iter()
+                    .fold(solved.clone(), |places, mv| stickers.apply(&places, mv))
+            };
+            assert_eq!(places("R L U' D"), places("L R D U'"));
+            assert_eq!(places("x2 y2"), places("z2"));
+            assert_eq!(places("(R U R' U')6"), solved);
+            assert_eq!(places("R4"), solved);
+            assert_ne!(places("R"), solved);
+            assert_ne!(places("[R, U]"), solved);
+            if n >= 3 {
+                assert_eq!(places("M"), places("R L' x'"));
+            }
+        }
+    }
+}
@@ -0,0 +1,309 @@
+use crate::{parse, Definitions, Move, ParseError, ParseMode, Tree};
+use alloc::vec::Vec;
+use core::{ops::Range, ptr};
+use cube::{Cube, EdgeSticker, RotatedCube};
+
+/// The number of moves between the cubes a [`Cursor`] keeps.
+const CHECKPOINT_INTERVAL: usize = 32;
+
+/// A move of an alg as it is performed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CursorStep {
+    pub mv: Move,
+    /// The bytes of the move in the text of the alg. Moves of inverted
+    /// parts, like the second `R` of `[R, U]`, point at the written move.
+    pub span: Range<usize>,
+    /// The number of conjugates and commutators the move is in.
+    pub depth: usize,
+}
+
+/// A position in an alg, for stepping through it move by move.
+///
+/// The cursor keeps the cube at every few moves, so getting the cube at any
+/// position only performs the moves since the last of them.
+pub struct Cursor {
+    tree: Tree,
+    steps: Vec<CursorStep>,
+    checkpoints: Vec<(Cube, EdgeSticker)>,
+    position: usize,
+}
+
+/// Returns the number of spans of `tree`, which are its moves and macro
+/// names.
+fn written_moves(tree: &Tree) -> usize {
+    match tree {
+        Tree::Move(_) | Tree::Macro(..) => 1,
+        Tree::MoveSet(moves) => moves.len(),
+        Tree::Group(_, trees) => trees.iter().map(written_moves).sum(),
+        Tree::Conj(_, a, b) | Tree::Comm(_, a, b) | Tree::Slash(_, a, b) => {
+            written_moves(a) + written_moves(b)
+        }
+        Tree::Repeat(tree, _) | Tree::Niss(tree) => written_moves(tree),
+        Tree::Insertion => 0,
+    }
+}
+
+/// Pushes the steps of `tree`, whose first written move has the span
+/// `spans[first]`.
+fn push_steps(
+    steps: &mut Vec<CursorStep>,
+    spans: &[Range<usize>],
+    tree: &Tree,
+    invert: bool,
+    depth: usize,
+    first: usize,
+) {
+    match tree {
+        &Tree::Move(mv) => steps.push(CursorStep {
+            mv: if invert { mv.inverse() } else { mv },
+            span: spans[first].clone(),
+            depth,
+        }),
+        Tree::MoveSet(_) | Tree::Insertion => {}
+        Tree::Niss(tree) => push_steps(steps, spans, tree, invert, depth, first),
+        Tree::Macro(_, tree) => {
+            let moves = if invert {
+                tree.inverse_moves()
+            } else {
+                tree.moves()
+            };
+            steps.extend(moves.map(|mv| CursorStep {
+                mv,
+                span: spans[first].clone(),
+                depth,
+            }));
+        }
+        Tree::Group(_, trees) => {
+            let mut firsts = Vec::with_capacity(trees.len());
+            let mut written = first;
+            for tree in trees {
+                firsts.push(written);
+                written += written_moves(tree);
+            }
+            let children = trees.iter().zip(firsts);
+            if invert {
+                for (tree, first) in children.rev() {
+                    push_steps(steps, spans, tree, invert, depth, first);
+                }
+            } else {
+                for (tree, first) in children {
+                    push_steps(steps, spans, tree, invert, depth, first);
+                }
+            }
+        }
+        Tree::Repeat(child, _) => {
+            let mut index = 0;
+            while let Some((_, invert)) = tree.part(invert, index) {
+                push_steps(steps, spans, child, invert, depth, first);
+                index += 1;
+            }
+        }
+        Tree::Conj(_, a, _) | Tree::Comm(_, a, _) | Tree::Slash(_, a, _) => {
+            let mut index = 0;
+            while let Some((child, invert)) = tree.part(invert, index) {
+                let child_first = if ptr::eq(child, &**a) {
+                    first
+                } else {
+                    first + written_moves(a)
+                };
+                push_steps(steps, spans, child, invert, depth + 1, child_first);
+                index += 1;
+            }
+        }
+    }
+}
+
+impl Cursor {
+    /// Parses an alg and puts the cursor before its first move.
+    pub fn new(n: u16, mode: ParseMode, text: &str) -> Result<Cursor, ParseError> {
+        Cursor::with_definitions(n, mode, text, &Definitions::new(n))
+    }
+
+    /// Parses an alg that can use the names of `definitions`, whose moves
+    /// all point at the name.
+    pub fn with_definitions(
+        n: u16,
+        mode: ParseMode,
+        text: &str,
+        definitions: &Definitions,
+    ) -> Result<Cursor, ParseError> {
+        let parsed = parse(n, mode, text, Some(definitions))?;
+        let mut steps = Vec::new();
+        push_steps(&mut steps, &parsed.move_spans, &parsed.tree, false, 0, 0);
+
+        let mut cube = Cube::new_solved(n);
+        let mut orientation = EdgeSticker::Uf;
+        let mut checkpoints = Vec::from([(cube.clone(), orientation)]);
+        for chunk in steps.chunks(CHECKPOINT_INTERVAL) {
+            let mut rotated = RotatedCube {
+                cube: &mut cube,
+                orientation,
+            };
+            for step in chunk {
+                rotated.rotate(step.mv.face, step.mv.start..step.mv.end, step.mv.count);
+            }
+            orientation = rotated.orientation;
+            checkpoints.push((cube.clone(), orientation));
+        }
+
+        Ok(Cursor {
+            tree: parsed.tree,
+            steps,
+            checkpoints,
+            position: 0,
+        })
+    }
+
+    pub fn tree(&self) -> &Tree {
+        &self.tree
+    }
+
+    /// Returns every move of the alg, in the order it is performed.
+    pub fn steps(&self) -> &[CursorStep] {
+        &self.steps
+    }
+
+    /// Returns the number of moves performed so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Moves the cursor to after the first `position` moves, or to the end
+    /// of the alg if it is shorter.
+    pub fn seek(&mut self, position: usize) {
+        self.position = position.min(self.steps.len());
+    }
+
+    /// Performs the next move and returns it, or `None` at the end.
+    pub fn forward(&mut self) -> Option<&CursorStep> {
+        let step = self.steps.get(self.position)?;
+        self.position += 1;
+        Some(step)
+    }
+
+    /// Undoes the last move and returns it, or `None` at the start.
+    pub fn back(&mut self) -> Option<&CursorStep> {
+        self.position = self.position.checked_sub(1)?;
+        Some(&self.steps[self.position])
+    }
+
+    /// Returns the last move performed, which is the one to highlight.
+    pub fn current(&self) -> Option<&CursorStep> {
+        self.steps.get(self.position.checked_sub(1)?)
+    }
+
+    /// Returns the cube after the moves performed so far.
+    pub fn cube(&self) -> Cube {
+        self.cube_at(self.position)
+    }
+
+    /// Returns the cube after the first `position` moves, or after all of
+    /// them if the alg is shorter.
+    pub fn cube_at(&self, position: usize) -> Cube {
+        let position = position.min(self.steps.len());
+        let checkpoint = position / CHECKPOINT_INTERVAL;
+        let (cube, orientation) = &self.checkpoints[checkpoint];
+        let mut cube = cube.clone();
+        let mut rotated = RotatedCube {
+            cube: &mut cube,
+            orientation: *orientation,
+        };
+        for step in &self.steps[checkpoint * CHECKPOINT_INTERVAL..position] {
+            rotated.rotate(step.mv.face, step.mv.start..step.mv.end, step.mv.count);
+        }
+        cube
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{format, string::String};
+    use expect_test::{expect, Expect};
+
+    #[test]
+    fn steps() {
+        #[track_caller]
+        fn check(alg: &str, expected: Expect) {
+            let cursor = Cursor::new(3, ParseMode::Wca, alg).unwrap();
+            let mut actual = String::new();
+            for step in cursor.steps() {
+                let written = crate::parse_alg(3, ParseMode::Wca, &alg[step.span.clone()]);
+                let written = written.unwrap().to_moves();
+                assert!(written == [step.mv] || written == [step.mv.inverse()]);
+                actual += &format!("{:?} {:?} {}\n", step.mv, step.span, step.depth);
+            }
+            expected.assert_eq(&actual);
+        }
+
+        check(
+            "R U2 R'",
+            expect![[r#"
+            R 0..1 0
+            U2 2..4 0
+            R' 5..7 0
+        "#]],
+        );
+        check(
+            "[R U: [D', R' U R]]",
+            expect![[r#"
+            R 1..2 1
+            U 3..4 1
+            D' 7..9 2
+            R' 11..13 2
+            U 14..15 2
+            R 16..17 2
+            D 7..9 2
+            R' 16..17 2
+            U' 14..15 2
+            R 11..13 2
+            U' 3..4 1
+            R' 1..2 1
+        "#]],
+        );
+        check(
+            "(<R, U> R U)2' M2",
+            expect![[r#"
+            U' 10..11 0
+            R' 8..9 0
+            U' 10..11 0
+            R' 8..9 0
+            M2 15..17 0
+        "#]],
+        );
+    }
+
+    #[test]
+    fn cubes() {
+        let alg = "[R U R' D, R' U2 R](R U R' U')10 [F: [U, R]]";
+        let mut cursor = Cursor::new(5, ParseMode::Wca, alg).unwrap();
+        let moves = cursor.tree().to_moves();
+        assert_eq!(cursor.steps().len(), moves.len());
+
+        for position in [0, 1, 31, 32, 33, 40, 64, moves.len(), moves.len() + 1] {
+            let tree = Tree::Group(
+                crate::Delimiter::None,
+                moves
+                    .iter()
+                    .take(position)
+                    .copied()
+                    .map(Tree::Move)
+                    .collect(),
+            );
+            let mut expected = Cube::new_solved(5);
+            tree.apply_to(&mut expected);
+            cursor.seek(position);
+            assert_eq!(cursor.cube(), expected);
+        }
+
+        cursor.seek(0);
+        assert_eq!(cursor.back(), None);
+        expect!["Some(R)"].assert_eq(&format!("{:?}", cursor.forward().map(|step| step.mv)));
+        expect!["Some(U)"].assert_eq(&format!("{:?}", cursor.forward().map(|step| step.mv)));
+        expect!["Some(U)"].assert_eq(&format!("{:?}", cursor.back().map(|step| step.mv)));
+        expect!["Some(R)"].assert_eq(&format!("{:?}", cursor.current().map(|step| step.mv)));
+        cursor.seek(usize::MAX);
+        assert_eq!(cursor.forward(), None);
+        assert_eq!(cursor.position(), moves.len());
+    }
+}
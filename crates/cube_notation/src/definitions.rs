@@ -0,0 +1,223 @@
+use crate::{parse, parse_alg, ParseError, ParseErrorKind, ParseMode, Tree};
+use alloc::{collections::BTreeMap, string::String};
+
+/// Named algs that other algs on an `n` by `n` cube can use, like `sexy`
+/// in `sexy3` after `sexy = R U R' U'`.
+///
+/// Names are made of letters and `_`, and are only recognized when they are
+/// not followed by another letter, so `sexy3` and `[A, D]` use the names
+/// `sexy` and `A` but `AB` does not use `A`. Names cannot be moves of the
+/// cube, like `S` or `x`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definitions {
+    n: u16,
+    trees: BTreeMap<String, Tree>,
+}
+
+pub(crate) fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+/// Returns whether `name` is a single move on an `n` by `n` cube in WCA or
+/// SiGN notation.
+fn is_move(n: u16, name: &str) -> bool {
+    [ParseMode::Wca, ParseMode::Sign].into_iter().any(|mode| {
+        let tree = parse_alg(n, mode, name);
+        matches!(tree, Ok(Tree::Group(_, trees)) if matches!(trees[..], [Tree::Move(_)]))
+    })
+}
+
+impl Definitions {
+    pub fn new(n: u16) -> Definitions {
+        Definitions {
+            n,
+            trees: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the size of the cube the definitions are for.
+    pub fn n(&self) -> u16 {
+        self.n
+    }
+
+    /// Defines `name` as `tree`, replacing any earlier definition.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is empty, contains anything but letters and `_`, or
+    /// is a move of the cube.
+    pub fn define(&mut self, name: &str, tree: Tree) {
+        assert!(!name.is_empty() && name.chars().all(is_name_char));
+        assert!(!is_move(self.n, name), "`{name}` is a move");
+        self.trees.insert(name.into(), tree);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Tree> {
+        self.trees.get(name)
+    }
+
+    /// Parses a definition like `sexy = R U R' U'` and adds it. The alg can
+    /// use the names defined before.
+    pub fn parse_definition(&mut self, mode: ParseMode, line: &str) -> Result<(), ParseError> {
+        let Some(equals) = line.find('=') else {
+            return Err(ParseError::new(
+                0..line.len(),
+                ParseErrorKind::InvalidDefinition,
+                Some("expected `name = alg`"),
+            ));
+        };
+        let name = line[..equals].trim();
+        let start = line.len() - line.trim_start().len();
+        let error = |hint| {
+            Err(ParseError::new(
+                start..start + name.len(),
+                ParseErrorKind::InvalidDefinition,
+                Some(hint),
+            ))
+        };
+        if name.is_empty() || !name.chars().all(is_name_char) {
+            return error("names are made of letters and `_`");
+        }
+        if is_move(self.n, name) {
+            return error("names cannot be moves");
+        }
+        let tree = parse_alg_with_definitions(self.n, mode, &line[equals + 1..], self)
+            .map_err(|e| e.offset(equals + 1))?;
+        self.define(name, tree);
+        Ok(())
+    }
+
+    /// Parses definitions written one per line, skipping empty lines.
+    pub fn parse(n: u16, mode: ParseMode, text: &str) -> Result<Definitions, ParseError> {
+        let mut definitions = Definitions::new(n);
+        let mut start = 0;
+        for line in text.split_inclusive('\n') {
+            let content = line.trim_end_matches(['\n', '\r']);
+            if !content.trim().is_empty() {
+                definitions
+                    .parse_definition(mode, content)
+                    .map_err(|e| e.offset(start))?;
+            }
+            start += line.len();
+        }
+        Ok(definitions)
+    }
+}
+
+/// Parses an alg like [`parse_alg`], where the names of `definitions` stand
+/// for their algs and become [`Tree::Macro`]s. The definitions must be for
+/// the same size of cube.
+pub fn parse_alg_with_definitions(
+    n: u16,
+    mode: ParseMode,
+    text: &str,
+    definitions: &Definitions,
+) -> Result<Tree, ParseError> {
+    parse(n, mode, text, Some(definitions)).map(|parsed| parsed.tree)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format_moves;
+    use alloc::format;
+    use cube::Axis;
+    use expect_test::{expect, Expect};
+
+    #[test]
+    fn definitions() {
+        let definitions = Definitions::parse(
+            3,
+            ParseMode::Wca,
+            "A = R U R'\n\nsexy = R U R' U'\nsledge_hammer = R' F R F'\nP = [sexy: U]\n",
+        )
+        .unwrap();
+
+        #[track_caller]
+        fn check(definitions: &Definitions, alg: &str, expected: Expect) {
+            let actual = match parse_alg_with_definitions(3, ParseMode::Wca, alg, definitions) {
+                Ok(tree) => format!(
+                    "{tree}\n{}\ninverse {}",
+                    format_moves(&tree.to_moves()),
+                    tree.inverse(),
+                ),
+                Err(e) => format!("{e}"),
+            };
+            expected.assert_eq(&actual);
+        }
+
+        check(
+            &definitions,
+            "[A, D]",
+            expect![[r#"
+                [A, D]
+                R U R' D R U' R' D'
+                inverse [D, A]"#]],
+        );
+        check(
+            &definitions,
+            "sexy3 sledge_hammer'",
+            expect![[r#"
+                sexy3 sledge_hammer'
+                R U R' U' R U R' U' R U R' U' F R' F' R
+                inverse sledge_hammer sexy3'"#]],
+        );
+        check(
+            &definitions,
+            "(P U)2 x",
+            expect![[r#"
+                (P U)2 x
+                R U R' U' U U R U' R' U R U R' U' U U R U' R' U x
+                inverse x' (P U)2'"#]],
+        );
+        check(&definitions, "AB", expect!["unknown move 'A' at 0..1"]);
+        check(
+            &Definitions::new(3),
+            "[A, D]",
+            expect!["unknown move 'A' at 1..2"],
+        );
+
+        let tree = parse_alg_with_definitions(3, ParseMode::Wca, "sexy", &definitions).unwrap();
+        expect!["L' U' L U"].assert_eq(&format!("{}", tree.mirror(Axis::X)));
+        expect!["Some(2)"].assert_eq(&format!("{:?}", tree.retarget(4).map(|t| t.min_size())));
+        let error =
+            parse_alg_with_definitions(4, ParseMode::Wca, "sexy", &definitions).unwrap_err();
+        expect!["invalid definition at 0..4: the definitions are for another size of cube"]
+            .assert_eq(&format!("{error}"));
+    }
+
+    #[test]
+    fn definition_errors() {
+        #[track_caller]
+        fn check(text: &str, expected: Expect) {
+            let error = Definitions::parse(3, ParseMode::Wca, text).unwrap_err();
+            expected.assert_eq(&format!("{error}"));
+        }
+
+        check(
+            "R U R'",
+            expect!["invalid definition at 0..6: expected `name = alg`"],
+        );
+        check(
+            "A = R\n b2 = U",
+            expect!["invalid definition at 7..9: names are made of letters and `_`"],
+        );
+        check(
+            "A = R\nC = [A, U",
+            expect!["unclosed '[' at 10..11: expected ']'"],
+        );
+        check("A = C\nC = R", expect!["unknown move 'C' at 4..5"]);
+        check(
+            "S = R U",
+            expect!["invalid definition at 0..1: names cannot be moves"],
+        );
+        check(
+            "A = R\n x = U",
+            expect!["invalid definition at 7..8: names cannot be moves"],
+        );
+        check(
+            "Y = R",
+            expect!["invalid definition at 0..1: names cannot be moves"],
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{parse_alg, Delimiter, Move, ParseError, ParseMode, Tree};
+use alloc::{
+    fmt::{self, Display},
+    string::String,
+    vec::Vec,
+};
+use core::ops::Range;
+
+/// One line of an alg document, like `cross: D R' F // easy`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// The text before the first `:` that is not inside brackets.
+    pub label: Option<String>,
+    pub tree: Tree,
+    /// The text after `//`.
+    pub comment: Option<String>,
+    /// The bytes of the alg in the document.
+    pub span: Range<usize>,
+}
+
+/// A reconstruction or alg sheet, with one step per line.
+///
+/// Blank lines and lines with only a comment are kept as steps with an
+/// empty alg, so formatting a document gives back the same lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Document {
+    pub steps: Vec<Step>,
+}
+
+impl Document {
+    /// Returns the moves of all steps, one after the other.
+    pub fn to_moves(&self) -> Vec<Move> {
+        let mut result = Vec::new();
+        for step in &self.steps {
+            step.tree.visit_moves(|mv| result.push(mv));
+        }
+        result
+    }
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut want_space = false;
+        if let Some(label) = &self.label {
+            write!(f, "{}:", label)?;
+            want_space = true;
+        }
+        if self.tree != Tree::Group(Delimiter::None, Vec::new()) {
+            if want_space {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}", self.tree)?;
+            want_space = true;
+        }
+        if let Some(comment) = &self.comment {
+            if want_space {
+                f.write_str(" ")?;
+            }
+            write!(f, "// {}", comment)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, step) in self.steps.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+            write!(f, "{}", step)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the position of the first `:` outside of brackets.
+fn label_end(line: &str) -> Option<usize> {
+    let mut depth = 0_usize;
+    for (i, c) in line.char_indices() {
+        match c {
+            '[' | '(' | '{' | '<' => depth += 1,
+            ']' | ')' | '}' | '>' => depth = depth.saturating_sub(1),
+            ':' if depth == 0 => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parses a document with one step per line.
+///
+/// Each line may start with a label ending in `:` and end with a comment
+/// starting with `//`. Since the first unbracketed `:` ends the label,
+/// conjugates have to be written with brackets, like `[U: R]`. Errors point
+/// at bytes of the whole document.
+pub fn parse_document(n: u16, mode: ParseMode, text: &str) -> Result<Document, ParseError> {
+    let mut steps = Vec::new();
+    let mut line_start = 0;
+    for line in text.split_inclusive('\n') {
+        let start = line_start;
+        line_start += line.len();
+        let line = line.trim_end_matches(['\n', '\r']);
+
+        let (line, comment) = match line.find("//") {
+            Some(i) => (&line[..i], Some(String::from(line[i + 2..].trim()))),
+            None => (line, None),
+        };
+
+        let (label, alg_start) = match label_end(line) {
+            Some(i) => (Some(String::from(line[..i].trim())), i + 1),
+            None => (None, 0),
+        };
+
+        let alg = &line[alg_start..];
+        let trimmed = alg.trim_start();
+        let alg_start = start + alg_start + (alg.len() - trimmed.len());
+        let alg = trimmed.trim_end();
+
+        let tree = parse_alg(n, mode, alg).map_err(|e| e.offset(alg_start))?;
+        steps.push(Step {
+            label,
+            tree,
+            comment,
+            span: alg_start..alg_start + alg.len(),
+        });
+    }
+    Ok(Document { steps })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format_moves;
+    use alloc::format;
+    use expect_test::expect;
+
+    #[test]
+    fn round_trip() {
+        let text = "\
+// Reconstruction
+inspection: x2 y
+
+cross:   D R' F   // easy
+f2l 1: [U: R U' R']
+(R U R' U')2 //sexy
+oll:";
+        let document = parse_document(3, ParseMode::Wca, text).unwrap();
+        let formatted = format!("{document}");
+        expect![[r#"
+            // Reconstruction
+            inspection: x2 y
+
+            cross: D R' F // easy
+            f2l 1: [U: R U' R']
+            (R U R' U')2 // sexy
+            oll:"#]]
+        .assert_eq(&formatted);
+        let reparsed = parse_document(3, ParseMode::Wca, &formatted).unwrap();
+        assert_eq!(format!("{reparsed}"), formatted);
+
+        let step = &document.steps[3];
+        expect!["D R' F"].assert_eq(&text[step.span.clone()]);
+        expect!["x2 y D R' F U R U' R' U' R U R' U' R U R' U'"]
+            .assert_eq(&format_moves(&document.to_moves()));
+    }
+
+    #[test]
+    fn errors() {
+        let error = parse_document(3, ParseMode::Wca, "cross: D\nf2l: R Q // oops").unwrap_err();
+        expect!["unknown move 'Q' at 16..17"].assert_eq(&format!("{error}"));
+        let error = parse_document(3, ParseMode::Wca, "// setup\noll: R [U, F").unwrap_err();
+        expect!["unclosed '[' at 16..17: expected ']'"].assert_eq(&format!("{error}"));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{rotate_from, Move, Tree};
+use alloc::vec::Vec;
+use cube::{Axis, Cube, EdgeSticker, Face, RotatedCube};
+
+/// What two algs may differ in and still count as [`equivalent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EquivalenceOptions {
+    /// Allows the algs to leave the cube in different orientations, like
+    /// `Rw` and `L`.
+    pub rotation: bool,
+    /// Allows a U turn before the second alg.
+    pub pre_auf: bool,
+    /// Allows a U turn after the second alg.
+    pub post_auf: bool,
+    /// Allows the second alg to be mirrored from left to right.
+    pub mirror: bool,
+}
+
+/// Returns the state of an `n` by `n` cube after `moves`, including the
+/// orientation it is held in.
+fn state(n: u16, moves: impl IntoIterator<Item = Move>) -> (Cube, EdgeSticker) {
+    let mut cube = Cube::new_solved(n);
+    let mut rotated = RotatedCube::new(&mut cube);
+    for mv in moves {
+        rotated.rotate(mv.face, mv.start..mv.end, mv.count);
+    }
+    let orientation = rotated.orientation;
+    (cube, orientation)
+}
+
+fn aufs(n: u16, allowed: bool) -> Vec<Option<Move>> {
+    let mut result = Vec::from([None]);
+    if allowed {
+        result.extend((1..4).map(|count| {
+            Some(Move {
+                n,
+                face: Face::U,
+                start: 0,
+                end: 1,
+                count,
+            })
+        }));
+    }
+    result
+}
+
+/// Returns whether `a` and `b` do the same to an `n` by `n` cube, up to what
+/// `options` allows.
+pub fn equivalent(n: u16, a: &Tree, b: &Tree, options: EquivalenceOptions) -> bool {
+    let a = a.to_moves();
+    let orientations = if options.rotation {
+        (0..24).map(EdgeSticker::from_index).collect()
+    } else {
+        Vec::from([EdgeSticker::Uf])
+    };
+    let targets = orientations
+        .into_iter()
+        .map(|orientation| state(n, a.iter().copied().chain(rotate_from(n, orientation))))
+        .collect::<Vec<_>>();
+
+    let mut variants = Vec::from([b.to_moves()]);
+    if options.mirror {
+        variants.push(b.mirror(Axis::X).to_moves());
+    }
+    for b in &variants {
+        for pre in aufs(n, options.pre_auf) {
+            for post in aufs(n, options.post_auf) {
+                let moves = pre.into_iter().chain(b.iter().copied()).chain(post);
+                if targets.contains(&state(n, moves)) {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_alg, ParseMode};
+    use alloc::format;
+
+    #[test]
+    fn equivalence() {
+        #[track_caller]
+        fn check(n: u16, a: &str, b: &str, options: EquivalenceOptions, expected: bool) {
+            let a = parse_alg(n, ParseMode::Wca, a).unwrap();
+            let b = parse_alg(n, ParseMode::Wca, b).unwrap();
+            assert_eq!(equivalent(n, &a, &b, options), expected);
+            assert_eq!(equivalent(n, &b, &a, options), expected);
+        }
+
+        let exact = EquivalenceOptions::default();
+        let rotation = EquivalenceOptions {
+            rotation: true,
+            ..exact
+        };
+        let auf = EquivalenceOptions {
+            pre_auf: true,
+            post_auf: true,
+            ..exact
+        };
+        let mirror = EquivalenceOptions {
+            mirror: true,
+            ..exact
+        };
+
+        check(3, "R U R' U'", "[R, U]", exact, true);
+        check(3, "R U R'", "R U' R'", exact, false);
+        check(3, "M2 U2 M2 U2", "(M2 U2)2", exact, true);
+        check(3, "Rw", "L", exact, false);
+        check(3, "Rw", "L", rotation, true);
+        check(3, "Rw", "L x", exact, true);
+        check(4, "3Rw", "L", rotation, true);
+        check(4, "3Rw", "L", exact, false);
+        check(4, "R U", "U R", rotation, false);
+
+        let t_perm = "R U R' U' R' F R2 U' R' U' R U R' F'";
+        check(3, t_perm, &format!("U {t_perm} U'"), exact, false);
+        check(3, t_perm, &format!("U {t_perm} U'"), auf, true);
+        check(3, t_perm, &format!("{t_perm} U"), auf, true);
+
+        check(3, "R U R' U R U2 R'", "L' U' L U' L' U2 L", exact, false);
+        check(3, "R U R' U R U2 R'", "L' U' L U' L' U2 L", mirror, true);
+    }
+}
@@ -0,0 +1,249 @@
+use crate::Move;
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+use cube::Face;
+
+/// A model of how hard moves are to execute, with the cost of every
+/// difficulty in moves.
+///
+/// The model follows the wrists of the hands holding the R and L faces,
+/// which turn the outer layers of their face and can only roll so far
+/// before the hand has to let go and regrip. Every other face is turned
+/// with the fingers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ergonomics {
+    /// The cost of every turn.
+    pub turn: f32,
+    /// The extra cost of turning each face, indexed by [`Face::index`].
+    /// Inner slices have no face cost.
+    pub faces: [f32; 6],
+    /// The extra cost of a half turn done with a wrist, like `R2` or `M2`.
+    pub half_turn: f32,
+    /// The extra cost of a half turn done with the fingers, like `U2`,
+    /// which takes two flicks.
+    pub double_flick: f32,
+    /// The extra cost of turning more than one outer layer, like `Rw`.
+    pub wide: f32,
+    /// The extra cost of turning inner layers only, like `M`.
+    pub slice: f32,
+    /// The cost of a rotation, which is not a turn.
+    pub rotation: f32,
+    /// The cost of letting go of the cube to bring a wrist back.
+    pub regrip: f32,
+}
+
+impl Default for Ergonomics {
+    /// Returns a model for right-handed speedsolving, where B and D turns
+    /// are the most awkward.
+    fn default() -> Ergonomics {
+        Ergonomics {
+            turn: 1.0,
+            faces: core::array::from_fn(|i| match Face::from_index(i) {
+                Face::U | Face::R => 0.0,
+                Face::F => 0.3,
+                Face::L | Face::D => 0.5,
+                Face::B => 1.0,
+            }),
+            half_turn: 0.2,
+            double_flick: 0.4,
+            wide: 0.2,
+            slice: 0.5,
+            rotation: 1.0,
+            regrip: 1.5,
+        }
+    }
+}
+
+/// A difficulty found in a sequence of moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CostKind {
+    Turn,
+    Face(Face),
+    HalfTurn,
+    DoubleFlick,
+    Wide,
+    Slice,
+    Rotation,
+    Regrip,
+}
+
+impl Display for CostKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CostKind::Turn => f.write_str("turn"),
+            CostKind::Face(face) => write!(f, "{:?} face", face),
+            CostKind::HalfTurn => f.write_str("half turn"),
+            CostKind::DoubleFlick => f.write_str("double flick"),
+            CostKind::Wide => f.write_str("wide move"),
+            CostKind::Slice => f.write_str("slice move"),
+            CostKind::Rotation => f.write_str("rotation"),
+            CostKind::Regrip => f.write_str("regrip"),
+        }
+    }
+}
+
+/// The cost of a difficulty at the move with index `index`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cost {
+    pub index: usize,
+    pub kind: CostKind,
+    pub cost: f32,
+}
+
+impl Display for Cost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}: {}", self.kind, self.index, self.cost)
+    }
+}
+
+/// The score of a sequence of moves, with the costs that add up to it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErgonomicsScore {
+    pub total: f32,
+    pub costs: Vec<Cost>,
+}
+
+impl Ergonomics {
+    /// Returns the cost of executing `moves`, where lower is easier.
+    pub fn score(&self, moves: &[Move]) -> ErgonomicsScore {
+        let mut costs = Vec::new();
+        // How far the right and left wrists are rolled, in quarter turns that
+        // bring the front layer up. They can roll twice that way but only
+        // once the other way.
+        let mut wrists = [0_i8; 2];
+        for (index, &mv) in moves.iter().enumerate() {
+            let mut add = |kind, cost: f32| {
+                if cost != 0.0 {
+                    costs.push(Cost { index, kind, cost });
+                }
+            };
+            if mv.is_rotation() {
+                add(CostKind::Rotation, self.rotation);
+                wrists = [0; 2];
+                continue;
+            }
+            add(CostKind::Turn, self.turn);
+
+            let slice = mv.start > 0;
+            if slice {
+                add(CostKind::Slice, self.slice);
+            } else {
+                add(CostKind::Face(mv.face), self.faces[mv.face.index()]);
+                if mv.end > 1 {
+                    add(CostKind::Wide, self.wide);
+                }
+            }
+
+            let wrist = match mv.face {
+                Face::R if !slice => Some((0_usize, mv.count)),
+                Face::L if !slice => Some((1, 4 - mv.count)),
+                _ => None,
+            };
+            match wrist {
+                Some((hand, count)) => {
+                    let position = &mut wrists[hand];
+                    let delta = match count {
+                        1 => 1,
+                        3 => -1,
+                        _ if *position <= 0 => 2,
+                        _ => -2,
+                    };
+                    if !(-1..=2).contains(&(*position + delta)) {
+                        add(CostKind::Regrip, self.regrip);
+                        *position = 0;
+                    }
+                    *position += delta;
+                }
+                None if mv.count == 2 && !slice => add(CostKind::DoubleFlick, self.double_flick),
+                None => {}
+            }
+            if mv.count == 2 && (slice || wrist.is_some()) {
+                add(CostKind::HalfTurn, self.half_turn);
+            }
+        }
+        ErgonomicsScore {
+            total: costs.iter().map(|cost| cost.cost).sum(),
+            costs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_alg, ParseMode};
+    use alloc::format;
+    use expect_test::{expect, Expect};
+
+    #[test]
+    fn ergonomics() {
+        #[track_caller]
+        fn check(n: u16, alg: &str, expected: Expect) {
+            let moves = parse_alg(n, ParseMode::Wca, alg).unwrap().to_moves();
+            let score = Ergonomics::default().score(&moves);
+            let mut actual = format!("{:.1}", score.total);
+            for cost in &score.costs {
+                if cost.kind != CostKind::Turn {
+                    actual += &format!("\n{cost}");
+                }
+            }
+            expected.assert_eq(&actual);
+        }
+
+        check(3, "R U R' U'", expect!["4.0"]);
+        check(
+            3,
+            "R' F R F'",
+            expect![[r#"
+                4.6
+                F face at 1: 0.3
+                F face at 3: 0.3"#]],
+        );
+        check(
+            3,
+            "R U2 R2 D B'",
+            expect![[r#"
+                7.1
+                double flick at 1: 0.4
+                half turn at 2: 0.2
+                D face at 3: 0.5
+                B face at 4: 1"#]],
+        );
+        check(
+            3,
+            "R R R",
+            expect![[r#"
+                4.5
+                regrip at 2: 1.5"#]],
+        );
+        check(
+            3,
+            "L' L' L R' R'",
+            expect![[r#"
+                8.0
+                L face at 0: 0.5
+                L face at 1: 0.5
+                L face at 2: 0.5
+                regrip at 4: 1.5"#]],
+        );
+        check(
+            3,
+            "x M2 r",
+            expect![[r#"
+                3.9
+                rotation at 0: 1
+                slice move at 1: 0.5
+                half turn at 1: 0.2
+                wide move at 2: 0.2"#]],
+        );
+
+        let t_perm = parse_alg(3, ParseMode::Wca, "R U R' U' R' F R2 F' U' R' U' R U R' F'")
+            .unwrap()
+            .to_moves();
+        let mirrored = parse_alg(3, ParseMode::Wca, "L' U' L U L F' L2 F U L U L' U' L F")
+            .unwrap()
+            .to_moves();
+        let model = Ergonomics::default();
+        assert!(model.score(&t_perm).total < model.score(&mirrored).total);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{Move, Tree};
+use alloc::{vec, vec::Vec};
+
+#[derive(Clone)]
+struct Frame<'a> {
+    tree: &'a Tree,
+    invert: bool,
+    /// The next part of the tree to visit.
+    index: usize,
+}
+
+/// An iterator over the moves of a [`Tree`], which expands commutators and
+/// repetitions only as far as it has been advanced.
+#[derive(Clone)]
+pub struct Moves<'a> {
+    root: &'a Tree,
+    invert: bool,
+    stack: Vec<Frame<'a>>,
+    len: usize,
+    remaining: usize,
+}
+
+impl<'a> Moves<'a> {
+    fn new(root: &'a Tree, invert: bool) -> Moves<'a> {
+        let len = root.num_moves();
+        Moves {
+            root,
+            invert,
+            stack: vec![Frame {
+                tree: root,
+                invert,
+                index: 0,
+            }],
+            len,
+            remaining: len,
+        }
+    }
+
+    /// Moves the iterator so that the next move is the `index`th one,
+    /// counting from the first move of the iteration.
+    ///
+    /// This skips whole parts of the tree, so seeking into a long repetition
+    /// takes as long as seeking into a single repeat of it.
+    pub fn seek(&mut self, index: usize) {
+        self.stack.clear();
+        self.remaining = self.len.saturating_sub(index);
+        if self.remaining == 0 {
+            return;
+        }
+        self.stack.push(Frame {
+            tree: self.root,
+            invert: self.invert,
+            index: 0,
+        });
+
+        let mut skip = index;
+        while skip > 0 {
+            let frame = self.stack.last_mut().unwrap();
+            let (tree, invert) = frame.tree.part(frame.invert, frame.index).unwrap();
+            let len = tree.num_moves();
+            if skip < len {
+                frame.index += 1;
+                self.stack.push(Frame {
+                    tree,
+                    invert,
+                    index: 0,
+                });
+            } else {
+                let whole = match frame.tree {
+                    Tree::Repeat(_, count) if len > 0 => {
+                        (skip / len).min(count.unsigned_abs() as usize - frame.index)
+                    }
+                    _ => 1,
+                };
+                frame.index += whole;
+                skip -= whole * len;
+            }
+        }
+    }
+}
+
+impl Iterator for Moves<'_> {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Move> {
+        loop {
+            let frame = self.stack.last_mut()?;
+            if let &Tree::Move(mv) = frame.tree {
+                let invert = frame.invert;
+                self.stack.pop();
+                self.remaining -= 1;
+                return Some(if invert { mv.inverse() } else { mv });
+            }
+            match frame.tree.part(frame.invert, frame.index) {
+                Some((tree, _)) if tree.num_moves() == 0 => match frame.tree {
+                    // Every copy of a repeated empty tree is empty too.
+                    Tree::Repeat(..) => {
+                        self.stack.pop();
+                    }
+                    _ => frame.index += 1,
+                },
+                Some((tree, invert)) => {
+                    frame.index += 1;
+                    self.stack.push(Frame {
+                        tree,
+                        invert,
+                        index: 0,
+                    });
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Moves<'_> {}
+
+impl Tree {
+    /// Returns an iterator over the moves of [`Tree::to_moves`].
+    pub fn moves(&self) -> Moves<'_> {
+        Moves::new(self, false)
+    }
+
+    /// Returns an iterator over the moves of [`Tree::to_inverse_moves`].
+    pub fn inverse_moves(&self) -> Moves<'_> {
+        Moves::new(self, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{format_moves, parse_alg, ParseMode};
+    use alloc::format;
+    use expect_test::expect;
+
+    #[test]
+    fn moves() {
+        let algs = [
+            "",
+            "R U R' U'",
+            "[R: [U, R D R']] (R U)3'",
+            "[R U, D] <R, U> M2 / U",
+            "((R) () U2)2 [(F)2: []]",
+        ];
+        for alg in algs {
+            let tree = parse_alg(3, ParseMode::Wca, alg).unwrap();
+            for (moves, expected) in [
+                (tree.moves(), tree.to_moves()),
+                (tree.inverse_moves(), tree.to_inverse_moves()),
+            ] {
+                assert_eq!(moves.len(), expected.len());
+                for index in 0..=expected.len() + 1 {
+                    let mut moves = moves.clone();
+                    moves.seek(index);
+                    let rest = expected.get(index..).unwrap_or_default();
+                    assert_eq!(moves.len(), rest.len());
+                    assert_eq!(moves.collect::<Vec<_>>(), rest);
+                }
+            }
+        }
+
+        let tree = parse_alg(3, ParseMode::Wca, "(R U R' U')10000 D").unwrap();
+        let mut moves = tree.inverse_moves();
+        expect!["40001"].assert_eq(&format!("{}", moves.len()));
+        moves.seek(39998);
+        expect!["R U' R'"].assert_eq(&format_moves(&moves.collect::<Vec<_>>()));
+
+        let tree = parse_alg(3, ParseMode::Wca, "(([] ())65535)65535 R").unwrap();
+        expect!["R"].assert_eq(&format_moves(&tree.moves().collect::<Vec<_>>()));
+    }
+}
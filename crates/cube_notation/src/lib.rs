@@ -8,8 +8,33 @@ use alloc::{
     vec,
     vec::Vec,
 };
-use core::mem::swap;
+use core::{mem::swap, ops::Range};
 use cube::{map_orientation, rotate_face, Axis, Cube, EdgeSticker, Face, RotatedCube};
+use definitions::is_name_char;
+
+pub mod commutator;
+pub mod cursor;
+pub mod definitions;
+pub mod document;
+pub mod equivalence;
+pub mod ergonomics;
+pub mod iter;
+pub mod link;
+pub mod metric;
+pub mod niss;
+pub mod retarget;
+
+pub use commutator::find_commutator;
+pub use cursor::{Cursor, CursorStep};
+pub use definitions::{parse_alg_with_definitions, Definitions};
+pub use document::{parse_document, Document, Step};
+pub use equivalence::{equivalent, EquivalenceOptions};
+pub use ergonomics::{Cost, CostKind, Ergonomics, ErgonomicsScore};
+pub use iter::Moves;
+pub use link::{parse_alg_link, AlgLink, AlgSite, LinkError};
+pub use metric::{move_count, Metric};
+pub use niss::{combine_niss, NissParts};
+pub use retarget::{GenericMove, Layers};
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Move {
@@ -42,6 +67,23 @@ impl Move {
     }
 }
 
+/// Returns the orientation after doing the rotation `mv` in `orientation`.
+fn rotate_orientation(orientation: EdgeSticker, mv: Move) -> EdgeSticker {
+    let (axis, invert) = match mv.face {
+        Face::R => (Axis::X, false),
+        Face::L => (Axis::X, true),
+        Face::U => (Axis::Y, false),
+        Face::D => (Axis::Y, true),
+        Face::F => (Axis::Z, false),
+        Face::B => (Axis::Z, true),
+    };
+    let count = if invert { 4 - mv.count % 4 } else { mv.count };
+    EdgeSticker::from_faces(
+        rotate_face(map_orientation(orientation, Face::U), axis, count),
+        rotate_face(map_orientation(orientation, Face::F), axis, count),
+    )
+}
+
 #[derive(Debug)]
 pub struct Canceler {
     pub moves: Vec<Move>,
@@ -101,19 +143,7 @@ impl Canceler {
 
     pub fn cancel(&mut self, mv: Move) {
         if mv.start == 0 && mv.end == mv.n {
-            let (axis, invert) = match mv.face {
-                Face::R => (Axis::X, false),
-                Face::L => (Axis::X, true),
-                Face::U => (Axis::Y, false),
-                Face::D => (Axis::Y, true),
-                Face::F => (Axis::Z, false),
-                Face::B => (Axis::Z, true),
-            };
-            let count = if invert { 4 - mv.count % 4 } else { mv.count };
-            self.orientation = EdgeSticker::from_faces(
-                rotate_face(map_orientation(self.orientation, Face::U), axis, count),
-                rotate_face(map_orientation(self.orientation, Face::F), axis, count),
-            );
+            self.orientation = rotate_orientation(self.orientation, mv);
             return;
         }
 
@@ -195,6 +225,22 @@ pub enum Tree {
     Conj(Delimiter, Box<Tree>, Box<Tree>),
     Comm(Delimiter, Box<Tree>, Box<Tree>),
     Slash(Delimiter, Box<Tree>, Box<Tree>),
+    /// A subtree repeated `count` times, like `(R U)3`, and inverted if
+    /// `count` is negative, like `[R, U]'`.
+    Repeat(Box<Tree>, i32),
+    /// An annotation naming the moves a part of an alg is restricted to,
+    /// like `<R, U>`. It does not turn anything.
+    MoveSet(Vec<Move>),
+    /// A subtree from [`Definitions`], like `sexy`, which is displayed by
+    /// its name.
+    Macro(String, Box<Tree>),
+    /// Moves done on the inverse scramble, like `(R U)` in
+    /// [`ParseMode::Fmc`]. They are part of [`Tree::to_moves`] as written;
+    /// [`Tree::niss_solution`] puts them where they belong.
+    Niss(Box<Tree>),
+    /// A point to insert moves at, written `^` in [`ParseMode::Fmc`]. It does
+    /// not turn anything.
+    Insertion,
 }
 
 impl Display for Tree {
@@ -204,67 +250,92 @@ impl Display for Tree {
 }
 
 impl Tree {
-    // TODO test if this really works for the inverse in all cases
-    fn visit_moves_internal(&self, f: &mut impl FnMut(Move), invert: bool) {
+    /// Returns the `index`th part of the tree and whether it is inverted,
+    /// which for a commutator `[A, B]` are `A`, `B`, `A'` and `B'`.
+    fn part(&self, invert: bool, index: usize) -> Option<(&Tree, bool)> {
         match self {
-            &Tree::Move(mv) => {
-                if invert {
-                    f(mv.inverse());
-                } else {
-                    f(mv);
-                }
-            }
+            Tree::Move(_) | Tree::MoveSet(_) | Tree::Insertion => None,
             Tree::Group(_, trees) => {
-                if invert {
-                    for tree in trees.iter().rev() {
-                        tree.visit_moves_internal(f, invert);
-                    }
+                let tree = if invert {
+                    trees.iter().rev().nth(index)
                 } else {
-                    for tree in trees {
-                        tree.visit_moves_internal(f, invert);
-                    }
-                }
+                    trees.get(index)
+                };
+                tree.map(|tree| (tree, invert))
             }
             Tree::Conj(_, a, b) => {
-                if invert {
-                    a.visit_moves_internal(f, !invert);
-                    b.visit_moves_internal(f, invert);
-                    a.visit_moves_internal(f, invert);
+                let parts = if invert {
+                    [(a, !invert), (b, invert), (a, invert)]
                 } else {
-                    a.visit_moves_internal(f, invert);
-                    b.visit_moves_internal(f, invert);
-                    a.visit_moves_internal(f, !invert);
-                }
+                    [(a, invert), (b, invert), (a, !invert)]
+                };
+                parts.get(index).map(|&(tree, invert)| (&**tree, invert))
             }
             Tree::Comm(_, a, b) => {
-                if invert {
-                    b.visit_moves_internal(f, !invert);
-                    a.visit_moves_internal(f, !invert);
-                    b.visit_moves_internal(f, invert);
-                    a.visit_moves_internal(f, invert);
+                let parts = if invert {
+                    [(b, !invert), (a, !invert), (b, invert), (a, invert)]
                 } else {
-                    a.visit_moves_internal(f, invert);
-                    b.visit_moves_internal(f, invert);
-                    a.visit_moves_internal(f, !invert);
-                    b.visit_moves_internal(f, !invert);
-                }
+                    [(a, invert), (b, invert), (a, !invert), (b, !invert)]
+                };
+                parts.get(index).map(|&(tree, invert)| (&**tree, invert))
             }
             Tree::Slash(_, a, b) => {
-                if invert {
-                    a.visit_moves_internal(f, !invert);
-                    b.visit_moves_internal(f, invert);
-                    a.visit_moves_internal(f, !invert);
-                    a.visit_moves_internal(f, !invert);
-                    b.visit_moves_internal(f, !invert);
-                    a.visit_moves_internal(f, !invert);
+                let parts = if invert {
+                    [
+                        (a, invert),
+                        (b, !invert),
+                        (a, invert),
+                        (a, invert),
+                        (b, invert),
+                        (a, invert),
+                    ]
                 } else {
-                    a.visit_moves_internal(f, invert);
-                    b.visit_moves_internal(f, invert);
-                    a.visit_moves_internal(f, invert);
-                    a.visit_moves_internal(f, invert);
-                    b.visit_moves_internal(f, !invert);
-                    a.visit_moves_internal(f, invert);
+                    [
+                        (a, invert),
+                        (b, invert),
+                        (a, invert),
+                        (a, invert),
+                        (b, !invert),
+                        (a, invert),
+                    ]
+                };
+                parts.get(index).map(|&(tree, invert)| (&**tree, invert))
+            }
+            Tree::Repeat(tree, count) => {
+                (index < count.unsigned_abs() as usize).then_some((&**tree, invert ^ (*count < 0)))
+            }
+            Tree::Macro(_, tree) | Tree::Niss(tree) => (index == 0).then_some((&**tree, invert)),
+        }
+    }
+
+    fn visit_moves_internal(&self, f: &mut impl FnMut(Move), invert: bool) {
+        if let &Tree::Move(mv) = self {
+            f(if invert { mv.inverse() } else { mv });
+            return;
+        }
+        let mut index = 0;
+        while let Some((tree, invert)) = self.part(invert, index) {
+            tree.visit_moves_internal(f, invert);
+            index += 1;
+        }
+    }
+
+    /// Returns the number of moves of the tree, without allocating them like
+    /// [`Tree::to_moves`].
+    pub fn num_moves(&self) -> usize {
+        match self {
+            Tree::Move(_) => 1,
+            Tree::Repeat(tree, count) => tree
+                .num_moves()
+                .saturating_mul(count.unsigned_abs() as usize),
+            _ => {
+                let mut len = 0_usize;
+                let mut index = 0;
+                while let Some((tree, _)) = self.part(false, index) {
+                    len = len.saturating_add(tree.num_moves());
+                    index += 1;
                 }
+                len
             }
         }
     }
@@ -320,8 +391,8 @@ impl Tree {
         result
     }
 
-    pub fn to_tokens(&self) -> Vec<Token> {
-        fn collect(tree: &Tree, out: &mut Vec<Token>) {
+    pub fn to_tokens(&self) -> Vec<Token<'_>> {
+        fn collect<'a>(tree: &'a Tree, out: &mut Vec<Token<'a>>) {
             match tree {
                 &Tree::Move(mv) => {
                     out.push(Token::Move(mv));
@@ -341,12 +412,51 @@ impl Tree {
                         Tree::Conj(..) => Token::Colon,
                         Tree::Comm(..) => Token::Comma,
                         Tree::Slash(..) => Token::Slash,
-                        Tree::Move(..) | Tree::Group(..) => unreachable!(),
+                        Tree::Move(..)
+                        | Tree::Group(..)
+                        | Tree::Repeat(..)
+                        | Tree::MoveSet(..)
+                        | Tree::Macro(..)
+                        | Tree::Niss(..)
+                        | Tree::Insertion => unreachable!(),
                     });
                     out.push(Token::Space);
                     collect(b, out);
                     out.push(Token::RBracket);
                 }
+                Tree::Repeat(tree, count) => {
+                    if let Tree::Conj(..) | Tree::Comm(..) | Tree::Slash(..) | Tree::Macro(..) =
+                        **tree
+                    {
+                        collect(tree, out);
+                    } else {
+                        out.push(Token::LParen);
+                        collect(tree, out);
+                        out.push(Token::RParen);
+                    }
+                    out.push(Token::Repeat(*count));
+                }
+                Tree::MoveSet(moves) => {
+                    out.push(Token::LAngle);
+                    for (i, &mv) in moves.iter().enumerate() {
+                        if i > 0 {
+                            out.push(Token::Comma);
+                        }
+                        out.push(Token::Move(mv));
+                    }
+                    out.push(Token::RAngle);
+                }
+                Tree::Macro(name, _) => out.push(Token::Name(name)),
+                Tree::Niss(tree) => {
+                    if let Tree::Repeat(..) = **tree {
+                        collect(tree, out);
+                    } else {
+                        out.push(Token::LParen);
+                        collect(tree, out);
+                        out.push(Token::RParen);
+                    }
+                }
+                Tree::Insertion => out.push(Token::Insertion),
             }
         }
 
@@ -354,10 +464,90 @@ impl Tree {
         collect(self, &mut result);
         result
     }
+
+    /// Returns the inverse of the tree, keeping its structure, so that the
+    /// inverse of `[A, B]` is `[B, A]` and the inverse of `[A: B]` is
+    /// `[A: B']`.
+    ///
+    /// Trees with [`Tree::Niss`] parts are inverted as FMC solutions, by
+    /// swapping the moves done on the normal and the inverse scramble, so
+    /// that [`Tree::niss_solution`] is inverted rather than
+    /// [`Tree::to_moves`].
+    pub fn inverse(&self) -> Tree {
+        match self {
+            _ if self.has_niss() => self.swap_niss(),
+            &Tree::Move(mv) => Tree::Move(mv.inverse()),
+            Tree::Group(delim, trees) => {
+                Tree::Group(*delim, trees.iter().rev().map(Tree::inverse).collect())
+            }
+            Tree::Conj(delim, a, b) => Tree::Conj(*delim, a.clone(), Box::new(b.inverse())),
+            Tree::Comm(delim, a, b) => Tree::Comm(*delim, b.clone(), a.clone()),
+            Tree::Slash(delim, a, b) => Tree::Slash(*delim, Box::new(a.inverse()), b.clone()),
+            Tree::Repeat(tree, count) => Tree::Repeat(tree.clone(), -count),
+            Tree::MoveSet(moves) => Tree::MoveSet(moves.clone()),
+            Tree::Macro(..) => Tree::Repeat(Box::new(self.clone()), -1),
+            Tree::Niss(_) => unreachable!(),
+            Tree::Insertion => Tree::Insertion,
+        }
+    }
+
+    /// Returns the tree with `f` applied to every move, keeping its
+    /// structure except for macros, which are replaced by their moves since
+    /// the name no longer fits.
+    fn map_moves(&self, f: &impl Fn(Move) -> Move) -> Tree {
+        let map = |tree: &Tree| Box::new(tree.map_moves(f));
+        match self {
+            &Tree::Move(mv) => Tree::Move(f(mv)),
+            Tree::Group(delim, trees) => {
+                Tree::Group(*delim, trees.iter().map(|tree| tree.map_moves(f)).collect())
+            }
+            Tree::Conj(delim, a, b) => Tree::Conj(*delim, map(a), map(b)),
+            Tree::Comm(delim, a, b) => Tree::Comm(*delim, map(a), map(b)),
+            Tree::Slash(delim, a, b) => Tree::Slash(*delim, map(a), map(b)),
+            Tree::Repeat(tree, count) => Tree::Repeat(map(tree), *count),
+            Tree::MoveSet(moves) => Tree::MoveSet(moves.iter().map(|&mv| f(mv)).collect()),
+            Tree::Macro(_, tree) => tree.map_moves(f),
+            Tree::Niss(tree) => Tree::Niss(map(tree)),
+            Tree::Insertion => Tree::Insertion,
+        }
+    }
+
+    /// Returns the mirror image of the tree through the plane perpendicular
+    /// to `axis`, so mirroring through [`Axis::X`] turns a right-handed alg
+    /// into a left-handed one.
+    pub fn mirror(&self, axis: Axis) -> Tree {
+        let axis_face = match axis {
+            Axis::X => Face::R,
+            Axis::Y => Face::U,
+            Axis::Z => Face::F,
+        };
+        self.map_moves(&|mv| {
+            let face = if mv.face == axis_face || mv.face == axis_face.opposite() {
+                mv.face.opposite()
+            } else {
+                mv.face
+            };
+            Move {
+                face,
+                ..mv.inverse()
+            }
+        })
+    }
+
+    /// Returns the tree performed with the cube held in `rotation`, which is
+    /// tracked like the orientation of a [`Canceler`]. Performing the
+    /// rotation and then the tree is the same as performing the transformed
+    /// tree and then the rotation.
+    pub fn transform(&self, rotation: EdgeSticker) -> Tree {
+        self.map_moves(&|mv| Move {
+            face: map_orientation(rotation, mv.face),
+            ..mv
+        })
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Token {
+pub enum Token<'a> {
     LBrace,
     RBrace,
     LBracket,
@@ -371,50 +561,98 @@ pub enum Token {
     Slash,
     Space,
     Move(Move),
+    /// The repetition suffix of a group, like the `2'` in `(R U)2'`.
+    Repeat(i32),
+    /// The name of a macro from [`Definitions`].
+    Name(&'a str),
+    /// The `^` marking an insertion point in [`ParseMode::Fmc`].
+    Insertion,
     End,
 }
 
 struct Parser<'a> {
-    tokens: &'a [Token],
+    tokens: &'a [Token<'a>],
+    spans: &'a [Range<usize>],
+    definitions: Option<&'a Definitions>,
+    /// Whether parentheses outside brackets are parts on the inverse
+    /// scramble, as in [`ParseMode::Fmc`].
+    fmc: bool,
     pos: usize,
 }
 
-impl Parser<'_> {
-    fn nth(&self, n: usize) -> Token {
+impl<'a> Parser<'a> {
+    fn nth(&self, n: usize) -> Token<'a> {
         self.tokens.get(self.pos + n).copied().unwrap_or(Token::End)
     }
 
-    fn bump(&mut self, token: Token) {
+    /// Returns the span of the current token, or an empty span at the end of
+    /// the text.
+    fn span(&self) -> Range<usize> {
+        match self.spans.get(self.pos) {
+            Some(span) => span.clone(),
+            None => {
+                let end = self.spans.last().map_or(0, |span| span.end);
+                end..end
+            }
+        }
+    }
+
+    fn bump(&mut self, token: Token<'a>) {
         assert_eq!(self.nth(0), token);
         self.pos += 1;
     }
 
-    fn mv(&mut self) -> Result<Option<Tree>, &'static str> {
+    fn unexpected(&self, c: char) -> ParseError {
+        ParseError::new(self.span(), ParseErrorKind::Unexpected(c), None)
+    }
+
+    fn mv(&mut self) -> Result<Option<Tree>, ParseError> {
         match self.nth(0) {
             Token::LBracket => {
+                let open = self.span();
                 self.bump(Token::LBracket);
-                Some(self.grouped(Delimiter::Brackets, "expected ']'")).transpose()
+                self.grouped(Delimiter::Brackets, open)
+                    .map(|tree| Some(self.repeat(tree)))
             }
             Token::LParen => {
+                let open = self.span();
                 self.bump(Token::LParen);
-                Some(self.grouped(Delimiter::Parens, "expected ')'")).transpose()
+                self.grouped(Delimiter::Parens, open)
+                    .map(|tree| Some(self.repeat(tree)))
             }
             Token::LBrace => {
+                let open = self.span();
                 self.bump(Token::LBrace);
-                Some(self.grouped(Delimiter::Braces, "expected '}'")).transpose()
+                self.grouped(Delimiter::Braces, open)
+                    .map(|tree| Some(self.repeat(tree)))
+            }
+            Token::LAngle => {
+                let open = self.span();
+                self.bump(Token::LAngle);
+                self.move_set(open).map(Some)
             }
-            Token::LAngle => Err("unexpected '<'"),
-            Token::RAngle => Err("unexpected '>'"),
-            Token::RBracket => Err("unexpected ']'"),
-            Token::RParen => Err("unexpected ')'"),
-            Token::RBrace => Err("unexpected '}'"),
-            Token::Comma => Err("unexpected ','"),
-            Token::Colon => Err("unexpected ':'"),
-            Token::Slash => Err("unexpected '/'"),
+            Token::RAngle => Err(self.unexpected('>')),
+            Token::RBracket => Err(self.unexpected(']')),
+            Token::RParen => Err(self.unexpected(')')),
+            Token::RBrace => Err(self.unexpected('}')),
+            Token::Comma => Err(self.unexpected(',')),
+            Token::Colon => Err(self.unexpected(':')),
+            Token::Slash => Err(self.unexpected('/')),
             Token::Move(mv) => {
                 self.bump(Token::Move(mv));
                 Ok(Some(Tree::Move(mv)))
             }
+            Token::Name(name) => {
+                self.bump(Token::Name(name));
+                let tree = self.definitions.and_then(|d| d.get(name)).unwrap();
+                let tree = Tree::Macro(name.into(), Box::new(tree.clone()));
+                Ok(Some(self.repeat(tree)))
+            }
+            Token::Insertion => {
+                self.bump(Token::Insertion);
+                Ok(Some(Tree::Insertion))
+            }
+            Token::Repeat(_) => unreachable!(),
             Token::End => Ok(None),
             Token::Space => {
                 self.bump(Token::Space);
@@ -423,15 +661,71 @@ impl Parser<'_> {
         }
     }
 
-    fn tree(&mut self) -> Result<Tree, &'static str> {
-        self.grouped(Delimiter::None, "")
+    /// Applies the repetition suffix following a group, if any.
+    fn repeat(&mut self, tree: Tree) -> Tree {
+        match self.nth(0) {
+            Token::Repeat(count) => {
+                self.bump(Token::Repeat(count));
+                Tree::Repeat(Box::new(tree), count)
+            }
+            _ => tree,
+        }
+    }
+
+    /// Parses the rest of a move set up to its closing `>`, where `open` is
+    /// the span of the opening `<`.
+    fn move_set(&mut self, open: Range<usize>) -> Result<Tree, ParseError> {
+        let mut moves = Vec::new();
+        loop {
+            match self.nth(0) {
+                Token::Move(mv) => {
+                    self.bump(Token::Move(mv));
+                    moves.push(mv);
+                }
+                token @ (Token::Comma | Token::Space) => self.bump(token),
+                Token::RAngle => {
+                    self.bump(Token::RAngle);
+                    return Ok(Tree::MoveSet(moves));
+                }
+                Token::End => {
+                    return Err(ParseError::new(
+                        open,
+                        ParseErrorKind::Unclosed('<'),
+                        Some("expected '>'"),
+                    ))
+                }
+                token => {
+                    let c = match token {
+                        Token::LBrace => '{',
+                        Token::RBrace => '}',
+                        Token::LBracket => '[',
+                        Token::RBracket => ']',
+                        Token::LParen => '(',
+                        Token::RParen => ')',
+                        Token::LAngle => '<',
+                        Token::Colon => ':',
+                        Token::Slash => '/',
+                        Token::Insertion => '^',
+                        Token::Name(name) => name.chars().next().unwrap(),
+                        _ => unreachable!(),
+                    };
+                    return Err(ParseError::new(
+                        self.span(),
+                        ParseErrorKind::Unexpected(c),
+                        Some("move sets can only contain moves"),
+                    ));
+                }
+            }
+        }
     }
 
-    fn grouped(
-        &mut self,
-        end: Delimiter,
-        expected_delim: &'static str,
-    ) -> Result<Tree, &'static str> {
+    fn tree(&mut self) -> Result<Tree, ParseError> {
+        self.grouped(Delimiter::None, 0..0)
+    }
+
+    /// Parses the rest of a group up to its closing delimiter, where `open`
+    /// is the span of its opening delimiter.
+    fn grouped(&mut self, end: Delimiter, open: Range<usize>) -> Result<Tree, ParseError> {
         let mut first = Vec::new();
         let delim = loop {
             match (end, self.nth(0)) {
@@ -454,11 +748,29 @@ impl Parser<'_> {
                     self.bump(Token::Slash);
                     break Token::Slash;
                 }
-                (_, Token::End) => return Err(expected_delim),
+                (Delimiter::None, Token::LParen) if self.fmc => {
+                    let open = self.span();
+                    self.bump(Token::LParen);
+                    let tree = self.grouped(Delimiter::Parens, open)?;
+                    first.push(Tree::Niss(Box::new(self.repeat(tree))));
+                }
+                (_, Token::End) => {
+                    let (c, hint) = match end {
+                        Delimiter::Braces => ('{', "expected '}'"),
+                        Delimiter::Brackets => ('[', "expected ']'"),
+                        Delimiter::Parens => ('(', "expected ')'"),
+                        Delimiter::None => unreachable!(),
+                    };
+                    return Err(ParseError::new(
+                        open,
+                        ParseErrorKind::Unclosed(c),
+                        Some(hint),
+                    ));
+                }
                 _ => first.extend(self.mv()?),
             }
         };
-        let second: Tree = self.grouped(end, expected_delim)?;
+        let second: Tree = self.grouped(end, open)?;
         let operator = match delim {
             Token::Comma => Tree::Comm,
             Token::Colon => Tree::Conj,
@@ -473,8 +785,102 @@ impl Parser<'_> {
     }
 }
 
-pub fn format_move<W: fmt::Write>(mut out: W, mut mv: Move) -> fmt::Result {
+/// How wide moves are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WideStyle {
+    /// `Rw` and `3Rw` as in WCA notation, with single inner slices written
+    /// like `r` and `3r`.
+    Suffix,
+    /// `r` and `3r` as in SiGN notation, with single inner slices written
+    /// like `2R` and `3R`.
+    Lowercase,
+}
+
+/// The house style used to write moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    pub wide: WideStyle,
+    /// Writes moves of inner layers as slices like `M` and `r`, instead of
+    /// as two moves of outer layers like `Lw L'`.
+    pub slices: bool,
+    /// The character of counterclockwise quarter turns.
+    pub prime: char,
+    /// Writes `2'` for inverted-letter half turns, which are half turns
+    /// written with a letter that turns the opposite way of the move, like
+    /// `M2'` for a middle layer turned twice from the R side. Other half
+    /// turns are always written as `2`.
+    pub double_prime: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> FormatOptions {
+        FormatOptions::from(ParseMode::Wca)
+    }
+}
+
+impl From<ParseMode> for FormatOptions {
+    fn from(mode: ParseMode) -> FormatOptions {
+        FormatOptions {
+            wide: match mode {
+                ParseMode::Wca | ParseMode::Lenient | ParseMode::Fmc => WideStyle::Suffix,
+                ParseMode::Sign => WideStyle::Lowercase,
+            },
+            slices: true,
+            prime: '\'',
+            double_prime: false,
+        }
+    }
+}
+
+pub fn format_move<W: fmt::Write>(out: W, mv: Move) -> fmt::Result {
+    format_move_in(out, mv, ParseMode::Wca)
+}
+
+pub fn format_move_in<W: fmt::Write>(out: W, mv: Move, mode: ParseMode) -> fmt::Result {
+    format_move_with(out, mv, &FormatOptions::from(mode))
+}
+
+/// Formats `mv` in the style of `options`, which may take two moves if
+/// slices are written as moves of outer layers.
+pub fn format_move_with<W: fmt::Write>(
+    mut out: W,
+    mv: Move,
+    options: &FormatOptions,
+) -> fmt::Result {
+    if options.slices || mv.start == 0 {
+        return write_move(&mut out, mv, options);
+    }
+    if mv.end == mv.n {
+        let opposite = Move {
+            face: mv.face.opposite(),
+            start: 0,
+            end: mv.n - mv.start,
+            ..mv.inverse()
+        };
+        return write_move(&mut out, opposite, options);
+    }
+    write_move(&mut out, Move { start: 0, ..mv }, options)?;
+    out.write_char(' ')?;
+    let inner = Move {
+        start: 0,
+        end: mv.start,
+        ..mv.inverse()
+    };
+    write_move(&mut out, inner, options)
+}
+
+fn write_move<W: fmt::Write>(out: &mut W, mut mv: Move, options: &FormatOptions) -> fmt::Result {
     assert!(mv.start <= mv.end);
+
+    let sign = options.wide == WideStyle::Lowercase;
+    let lowercase = match mv.face {
+        Face::L => 'l',
+        Face::R => 'r',
+        Face::D => 'd',
+        Face::U => 'u',
+        Face::F => 'f',
+        Face::B => 'b',
+    };
     let mut inv = false;
     mv.count %= 4;
     if mv.count == 3 {
@@ -482,9 +888,13 @@ pub fn format_move<W: fmt::Write>(mut out: W, mut mv: Move) -> fmt::Result {
         inv = true;
     }
     if mv.start == 0 && mv.end == 1 {
-        write!(&mut out, "{:?}", mv.face)?;
+        write!(out, "{:?}", mv.face)?;
     } else if mv.start == 0 && mv.end == 2 {
-        write!(&mut out, "{:?}w", mv.face)?;
+        if sign {
+            write!(out, "{}", lowercase)?;
+        } else {
+            write!(out, "{:?}w", mv.face)?;
+        }
     } else if mv.start == 0 && mv.end == mv.n {
         let (c, invert) = match mv.face {
             Face::R => ('x', false),
@@ -494,7 +904,7 @@ pub fn format_move<W: fmt::Write>(mut out: W, mut mv: Move) -> fmt::Result {
             Face::F => ('z', false),
             Face::B => ('z', true),
         };
-        write!(&mut out, "{}", c)?;
+        write!(out, "{}", c)?;
         inv ^= invert;
     } else if mv.start == 1 && mv.end == mv.n - 1 {
         let (c, invert) = match mv.face {
@@ -506,8 +916,8 @@ pub fn format_move<W: fmt::Write>(mut out: W, mut mv: Move) -> fmt::Result {
             Face::B => ('S', true),
         };
         inv ^= invert;
-        write!(&mut out, "{}", c)?;
-    } else if mv.n % 2 == 1 && mv.start == mv.n / 2 && mv.end == mv.n / 2 + 1 {
+        write!(out, "{}", c)?;
+    } else if !sign && mv.n % 2 == 1 && mv.start == mv.n / 2 && mv.end == mv.n / 2 + 1 {
         let (c, invert) = match mv.face {
             Face::L => ('m', false),
             Face::R => ('m', true),
@@ -517,33 +927,23 @@ pub fn format_move<W: fmt::Write>(mut out: W, mut mv: Move) -> fmt::Result {
             Face::B => ('s', true),
         };
         inv ^= invert;
-        write!(&mut out, "{}", c)?;
+        write!(out, "{}", c)?;
     } else if mv.start == 0 && mv.end > 1 {
-        write!(&mut out, "{}{:?}w", mv.end - mv.start, mv.face)?;
+        if sign {
+            write!(out, "{}{}", mv.end, lowercase)?;
+        } else {
+            write!(out, "{}{:?}w", mv.end, mv.face)?;
+        }
     } else if mv.start == mv.end - 1 {
-        let c = match mv.face {
-            Face::L => 'l',
-            Face::R => 'r',
-            Face::D => 'd',
-            Face::U => 'u',
-            Face::F => 'f',
-            Face::B => 'b',
-        };
-        if mv.start == 1 {
-            write!(&mut out, "{}", c)?;
+        if sign {
+            write!(out, "{}{:?}", mv.start + 1, mv.face)?;
+        } else if mv.start == 1 {
+            write!(out, "{}", lowercase)?;
         } else {
-            write!(&mut out, "{}{}", mv.start + 1, c)?;
+            write!(out, "{}{}", mv.start + 1, lowercase)?;
         }
     } else {
-        let c = match mv.face {
-            Face::L => 'l',
-            Face::R => 'r',
-            Face::D => 'd',
-            Face::U => 'u',
-            Face::F => 'f',
-            Face::B => 'b',
-        };
-        write!(&mut out, "{}-{}{}", mv.start - 1, mv.end - 2, c)?;
+        write!(out, "{}-{}{}", mv.start + 1, mv.end, lowercase)?;
     }
     if inv {
         mv.count = 4 - mv.count % 4;
@@ -552,15 +952,37 @@ pub fn format_move<W: fmt::Write>(mut out: W, mut mv: Move) -> fmt::Result {
         0 => out.write_char('0')?,
         1 => {}
         2 => out.write_char('2')?,
-        3 => out.write_char('\'')?,
+        3 => out.write_char(options.prime)?,
         _ => panic!(),
     }
+    if mv.count % 4 == 2 && inv && options.double_prime {
+        out.write_char(options.prime)?;
+    }
     Ok(())
 }
 
-pub fn format_tokens<W: fmt::Write>(
+pub fn format_tokens<'a, W: fmt::Write>(
+    out: W,
+    tokens: impl IntoIterator<Item = Token<'a>>,
+) -> fmt::Result {
+    format_tokens_in(out, tokens, ParseMode::Wca)
+}
+
+/// Formats `tokens` following the conventions of `mode`, which can be used
+/// to convert algs between notations.
+pub fn format_tokens_in<'a, W: fmt::Write>(
+    out: W,
+    tokens: impl IntoIterator<Item = Token<'a>>,
+    mode: ParseMode,
+) -> fmt::Result {
+    format_tokens_with(out, tokens, &FormatOptions::from(mode))
+}
+
+/// Formats `tokens` in the style of `options`.
+pub fn format_tokens_with<'a, W: fmt::Write>(
     mut out: W,
-    tokens: impl IntoIterator<Item = Token>,
+    tokens: impl IntoIterator<Item = Token<'a>>,
+    options: &FormatOptions,
 ) -> fmt::Result {
     let mut want_space = false;
     for token in tokens {
@@ -618,41 +1040,324 @@ pub fn format_tokens<W: fmt::Write>(
                 if want_space {
                     out.write_char(' ')?;
                 }
-                format_move(&mut out, mv)?;
+                format_move_with(&mut out, mv, options)?;
+                want_space = true;
+            }
+            Token::Name(name) => {
+                if want_space {
+                    out.write_char(' ')?;
+                }
+                out.write_str(name)?;
+                want_space = true;
+            }
+            Token::Insertion => {
+                if want_space {
+                    out.write_char(' ')?;
+                }
+                out.write_char('^')?;
+                want_space = true;
+            }
+            Token::Repeat(count) => {
+                if count.abs() != 1 {
+                    write!(&mut out, "{}", count.abs())?;
+                }
+                if count < 0 {
+                    out.write_char(options.prime)?;
+                }
                 want_space = true;
             }
             Token::End => {}
-            Token::LAngle => todo!(),
-            Token::RAngle => todo!(),
+            Token::LAngle => {
+                if want_space {
+                    out.write_char(' ')?;
+                }
+                out.write_char('<')?;
+                want_space = false;
+            }
+            Token::RAngle => {
+                out.write_char('>')?;
+                want_space = true;
+            }
         };
     }
     Ok(())
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A character that cannot appear where it is, like a stray `]`.
+    Unexpected(char),
+    /// A group opened with this character that is never closed.
+    Unclosed(char),
+    /// Layer specifiers or a count without a move letter.
+    MissingLetter,
+    /// A character that does not name a move.
+    UnknownMove(char),
+    /// A count too big to fit in a move.
+    CountOverflow,
+    /// A move turning more layers than the cube has.
+    MoveTooBig,
+    /// Layer specifiers or a wide marker on a move that does not take them.
+    InvalidLayers,
+    /// An inner slice move on a cube without inner slices.
+    NoInnerSlice,
+    /// A line of [`Definitions`] that is not like `name = alg`.
+    InvalidDefinition,
+}
+
+impl Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::Unexpected(c) => write!(f, "unexpected '{}'", c),
+            ParseErrorKind::Unclosed(c) => write!(f, "unclosed '{}'", c),
+            ParseErrorKind::MissingLetter => f.write_str("missing move letter"),
+            ParseErrorKind::UnknownMove(c) => write!(f, "unknown move '{}'", c),
+            ParseErrorKind::CountOverflow => f.write_str("count too big"),
+            ParseErrorKind::MoveTooBig => f.write_str("move too big"),
+            ParseErrorKind::InvalidLayers => f.write_str("invalid layers"),
+            ParseErrorKind::NoInnerSlice => f.write_str("no inner slice"),
+            ParseErrorKind::InvalidDefinition => f.write_str("invalid definition"),
+        }
+    }
+}
+
+/// An error in an alg, pointing at the bytes of the text that caused it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub span: Range<usize>,
+    pub kind: ParseErrorKind,
+    pub hint: Option<&'static str>,
+}
+
+impl ParseError {
+    fn new(span: Range<usize>, kind: ParseErrorKind, hint: Option<&'static str>) -> ParseError {
+        ParseError { span, kind, hint }
+    }
+
+    fn offset(mut self, by: usize) -> ParseError {
+        self.span = self.span.start + by..self.span.end + by;
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}..{}", self.kind, self.span.start, self.span.end)?;
+        if let Some(hint) = self.hint {
+            write!(f, ": {}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// A typo that was repaired in [`ParseMode::Lenient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseWarningKind {
+    /// A count after the prime, like `R'2`.
+    PrimeBeforeCount,
+    /// A character used as a prime, like `´` or `‘`.
+    Quote(char),
+    /// A `*` between moves, which is ignored.
+    Asterisk,
+    /// An uppercase rotation, like `X`.
+    UppercaseRotation(char),
+    /// Whitespace other than a space, like a tab.
+    Whitespace(char),
+}
+
+impl Display for ParseWarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseWarningKind::PrimeBeforeCount => f.write_str("count after prime"),
+            ParseWarningKind::Quote(c) => write!(f, "'{}' used as prime", c),
+            ParseWarningKind::Asterisk => f.write_str("stray '*'"),
+            ParseWarningKind::UppercaseRotation(c) => write!(f, "uppercase rotation '{}'", c),
+            ParseWarningKind::Whitespace(c) => write!(f, "unusual whitespace {:?}", c),
+        }
+    }
+}
+
+/// A repaired typo in an alg, pointing at the bytes of the text that were
+/// repaired.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+    pub span: Range<usize>,
+    pub kind: ParseWarningKind,
+}
+
+impl Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}..{}", self.kind, self.span.start, self.span.end)
+    }
+}
+
+/// The notation conventions used to parse and format algs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ParseMode {
+    /// WCA notation, where lowercase letters are inner slices on big cubes
+    /// and wide moves are written like `3Rw`.
     Wca,
+    /// SiGN notation, where lowercase letters are wide moves like `3r` and a
+    /// number before an uppercase letter picks a single inner slice like `3R`.
+    Sign,
+    /// WCA notation that repairs common typos, like `R'2`, `X` or a stray
+    /// `*`, and reports each repair as a [`ParseWarning`].
+    Lenient,
+    /// WCA notation for fewest moves solutions, where parentheses outside
+    /// brackets hold moves done on the inverse scramble, like `(R U)`, and
+    /// `^` marks an insertion point.
+    Fmc,
 }
 
-pub fn parse_alg(n: u16, ParseMode::Wca: ParseMode, text: &str) -> Result<Tree, &'static str> {
+pub fn parse_alg(n: u16, mode: ParseMode, text: &str) -> Result<Tree, ParseError> {
+    parse_alg_with_warnings(n, mode, text).map(|(tree, _)| tree)
+}
+
+/// Parses an alg like [`parse_alg`], also returning the typos that were
+/// repaired in [`ParseMode::Lenient`].
+pub fn parse_alg_with_warnings(
+    n: u16,
+    mode: ParseMode,
+    text: &str,
+) -> Result<(Tree, Vec<ParseWarning>), ParseError> {
+    parse(n, mode, text, None).map(|parsed| (parsed.tree, parsed.warnings))
+}
+
+struct Parsed {
+    tree: Tree,
+    warnings: Vec<ParseWarning>,
+    /// The bytes of every move and macro name in the order they are written.
+    move_spans: Vec<Range<usize>>,
+}
+
+fn parse(
+    n: u16,
+    mode: ParseMode,
+    text: &str,
+    definitions: Option<&Definitions>,
+) -> Result<Parsed, ParseError> {
+    if definitions.is_some_and(|d| d.n() != n) {
+        return Err(ParseError::new(
+            0..text.len(),
+            ParseErrorKind::InvalidDefinition,
+            Some("the definitions are for another size of cube"),
+        ));
+    }
+    let mut warnings = Vec::new();
     let mut i = 0;
     let mut tokens = Vec::new();
+    let mut spans = Vec::new();
     while i < text.len() {
-        let (tok, len) = tokenize(n, false, &text[i..])?;
+        let after_group = matches!(
+            tokens.last(),
+            Some(Token::RParen | Token::RBracket | Token::RBrace | Token::Name(_))
+        );
+        let repeat = if after_group {
+            tokenize_repeat(&text[i..]).map_err(|e| e.offset(i))?
+        } else {
+            None
+        };
+        let (tok, len) = match repeat {
+            Some(repeat) => repeat,
+            None => {
+                let first_warning = warnings.len();
+                let token = tokenize(n, mode, &text[i..], definitions, &mut warnings)
+                    .map_err(|e| e.offset(i))?;
+                for warning in &mut warnings[first_warning..] {
+                    warning.span = warning.span.start + i..warning.span.end + i;
+                }
+                token
+            }
+        };
         tokens.push(tok);
+        spans.push(i..i + len);
         i += len;
     }
     let mut parser = Parser {
         tokens: &tokens,
+        spans: &spans,
+        definitions,
+        fmc: mode == ParseMode::Fmc,
         pos: 0,
     };
     let tree = parser.tree()?;
-    Ok(tree)
+    let move_spans = tokens
+        .iter()
+        .zip(spans)
+        .filter(|(token, _)| matches!(token, Token::Move(_) | Token::Name(_)))
+        .map(|(_, span)| span)
+        .collect();
+    Ok(Parsed {
+        tree,
+        warnings,
+        move_spans,
+    })
 }
 
-fn tokenize(n: u16, sign: bool, text: &str) -> Result<(Token, usize), &'static str> {
+/// Tokenizes the repetition suffix at the start of `text`, if there is one.
+fn tokenize_repeat(text: &str) -> Result<Option<(Token<'_>, usize)>, ParseError> {
     let mut chars = text.char_indices().peekable();
+    let mut count = None;
+    while let Some(&(i, d)) = chars.peek() {
+        let Some(d) = d.to_digit(10) else {
+            break;
+        };
+        count = Some(
+            i32::checked_mul(count.unwrap_or(0), 10)
+                .and_then(|count| count.checked_add(d as i32))
+                .filter(|&count| count <= i32::from(u16::MAX))
+                .ok_or(ParseError::new(
+                    0..i + 1,
+                    ParseErrorKind::CountOverflow,
+                    None,
+                ))?,
+        );
+        chars.next();
+    }
+    let inverse = chars.next_if(|&(_, c)| c == '\'' || c == '’').is_some();
+    if count.is_none() && !inverse {
+        return Ok(None);
+    }
+    let count = count.unwrap_or(1);
+    let len = chars.peek().map_or(text.len(), |&(i, _)| i);
+    Ok(Some((
+        Token::Repeat(if inverse { -count } else { count }),
+        len,
+    )))
+}
+
+fn tokenize<'a>(
+    n: u16,
+    mode: ParseMode,
+    text: &'a str,
+    definitions: Option<&Definitions>,
+    warnings: &mut Vec<ParseWarning>,
+) -> Result<(Token<'a>, usize), ParseError> {
+    let mut chars = text.char_indices().peekable();
+    let lenient = mode == ParseMode::Lenient;
+    let name_len = text.find(|c: char| !is_name_char(c)).unwrap_or(text.len());
+    let name = &text[..name_len];
+    if definitions.is_some_and(|d| d.get(name).is_some()) {
+        return Ok((Token::Name(name), name_len));
+    }
     match chars.peek() {
         Some((_, ' ' | '!' | '+')) => Ok((Token::Space, 1)),
+        Some(&(_, '*')) if lenient => {
+            warnings.push(ParseWarning {
+                span: 0..1,
+                kind: ParseWarningKind::Asterisk,
+            });
+            Ok((Token::Space, 1))
+        }
+        Some(&(_, c)) if lenient && c.is_whitespace() => {
+            warnings.push(ParseWarning {
+                span: 0..c.len_utf8(),
+                kind: ParseWarningKind::Whitespace(c),
+            });
+            Ok((Token::Space, c.len_utf8()))
+        }
         Some((_, '{')) => Ok((Token::LBrace, 1)),
         Some((_, '}')) => Ok((Token::RBrace, 1)),
         Some((_, '[')) => Ok((Token::LBracket, 1)),
@@ -664,14 +1369,45 @@ fn tokenize(n: u16, sign: bool, text: &str) -> Result<(Token, usize), &'static s
         Some((_, ',')) => Ok((Token::Comma, 1)),
         Some((_, ':' | ';')) => Ok((Token::Colon, 1)),
         Some((_, '/')) => Ok((Token::Slash, 1)),
-        Some(_) => parse_move(n, sign, text).map(|(mv, len)| (Token::Move(mv), len)),
-        None => Err("empty string"),
+        Some((_, '^')) if mode == ParseMode::Fmc => Ok((Token::Insertion, 1)),
+        Some(_) => parse_move(n, mode, text, warnings).map(|(mv, len)| (Token::Move(mv), len)),
+        None => Err(ParseError::new(0..0, ParseErrorKind::MissingLetter, None)),
+    }
+}
+
+/// Returns the layers `first` to `last`, counted from 1, as a range counted
+/// from 0, reporting errors at `span`.
+fn layers(n: u16, first: u16, last: u16, span: Range<usize>) -> Result<(u16, u16), ParseError> {
+    if first == 0 {
+        return Err(ParseError::new(
+            span,
+            ParseErrorKind::InvalidLayers,
+            Some("layers are counted from 1"),
+        ));
+    }
+    if last < first {
+        return Err(ParseError::new(
+            span,
+            ParseErrorKind::InvalidLayers,
+            Some("layer ranges go from the outer to the inner layer"),
+        ));
     }
+    if last > n {
+        return Err(ParseError::new(span, ParseErrorKind::MoveTooBig, None));
+    }
+    Ok((first - 1, last))
 }
 
-fn parse_move(n: u16, sign: bool, text: &str) -> Result<(Move, usize), &'static str> {
+fn parse_move(
+    n: u16,
+    mode: ParseMode,
+    text: &str,
+    warnings: &mut Vec<ParseWarning>,
+) -> Result<(Move, usize), ParseError> {
+    let sign = mode == ParseMode::Sign;
+    let lenient = mode == ParseMode::Lenient;
     if text.is_empty() {
-        return Err("empty string");
+        return Err(ParseError::new(0..0, ParseErrorKind::MissingLetter, None));
     }
 
     let mut chars = text.char_indices().peekable();
@@ -690,28 +1426,82 @@ fn parse_move(n: u16, sign: bool, text: &str) -> Result<(Move, usize), &'static
         }
     }
 
-    let letter = match chars.next() {
-        Some((_, c)) => c,
-        None => return Err("invalid notation: missing letter"),
+    let (letter_index, letter) = match chars.next() {
+        Some(c) => c,
+        None => {
+            return Err(ParseError::new(
+                0..text.len(),
+                ParseErrorKind::MissingLetter,
+                None,
+            ))
+        }
     };
 
     let wide = chars.next_if(|&(_, c)| c == 'w').is_some();
 
-    if end.is_some() && wide {
-        return Err("invalid notation: cannot combine layer specifiers and wide marker");
-    }
-
     let mut count = None;
-    while let Some(d) = chars.peek().and_then(|(_, c)| c.to_digit(10)) {
-        count = Some(u8::checked_mul(count.unwrap_or(0), 10).ok_or("overflow")? + d as u8);
+    while let Some(&(i, d)) = chars.peek() {
+        let Some(d) = d.to_digit(10) else {
+            break;
+        };
+        count = Some(
+            u8::checked_mul(count.unwrap_or(0), 10)
+                .and_then(|count| count.checked_add(d as u8))
+                .ok_or(ParseError::new(
+                    0..i + 1,
+                    ParseErrorKind::CountOverflow,
+                    None,
+                ))?,
+        );
         chars.next();
     }
-    let mut count = count.unwrap_or(1);
+    let prime = chars
+        .next_if(|&(_, c)| c == '\'' || c == '’' || lenient && matches!(c, '‘' | '´' | '`' | '′'));
+    if let Some((i, c)) = prime {
+        if c != '\'' && c != '’' {
+            warnings.push(ParseWarning {
+                span: i..i + c.len_utf8(),
+                kind: ParseWarningKind::Quote(c),
+            });
+        }
+    }
 
-    if chars.next_if(|&(_, c)| c == '\'' || c == '’').is_some() {
+    if lenient && prime.is_some() && count.is_none() {
+        while let Some(d) = chars.peek().and_then(|(_, c)| c.to_digit(10)) {
+            count = Some(count.unwrap_or(0).wrapping_mul(10).wrapping_add(d as u8) % 4);
+            chars.next();
+        }
+        if count.is_some() {
+            let len = chars.peek().map_or(text.len(), |&(i, _)| i);
+            warnings.push(ParseWarning {
+                span: 0..len,
+                kind: ParseWarningKind::PrimeBeforeCount,
+            });
+        }
+    }
+
+    let mut count = count.unwrap_or(1);
+    if prime.is_some() {
         count = 4 - count % 4;
     }
 
+    let len = chars.peek().map_or(text.len(), |&(i, _)| i);
+    let error = |kind, hint| Err(ParseError::new(0..len, kind, hint));
+
+    // SiGN allows rotations in either case.
+    if lenient && matches!(letter, 'X' | 'Y' | 'Z') {
+        warnings.push(ParseWarning {
+            span: letter_index..letter_index + 1,
+            kind: ParseWarningKind::UppercaseRotation(letter),
+        });
+    }
+    let letter = match letter {
+        'X' if sign || lenient => 'x',
+        'Y' if sign || lenient => 'y',
+        'Z' if sign || lenient => 'z',
+        letter => letter,
+    };
+
     let mv = match letter {
         'U' | 'L' | 'F' | 'R' | 'B' | 'D' => {
             let face = match letter {
@@ -724,29 +1514,18 @@ fn parse_move(n: u16, sign: bool, text: &str) -> Result<(Move, usize), &'static
                 _ => todo!(),
             };
 
-            if wide {
-                let width = start.unwrap_or(2);
-                if width > n {
-                    return Err("move too big");
-                }
-                Move {
-                    n,
-                    face,
-                    start: 0,
-                    end: width,
-                    count,
-                }
-            } else {
-                if start.is_some() || end.is_some() {
-                    return Err("unimplemented notation: extended layer specifiers");
-                }
-                Move {
-                    n,
-                    face,
-                    start: 0,
-                    end: 1,
-                    count,
-                }
+            let (start, end) = match (start, end) {
+                (first, Some(last)) => layers(n, first.unwrap_or(0), last, 0..len)?,
+                (width, None) if wide => layers(n, 1, width.unwrap_or(2), 0..len)?,
+                (Some(layer), None) => layers(n, layer, layer, 0..len)?,
+                (None, None) => (0, 1),
+            };
+            Move {
+                n,
+                face,
+                start,
+                end,
+                count,
             }
         }
         'u' | 'l' | 'f' | 'r' | 'b' | 'd' => {
@@ -761,52 +1540,31 @@ fn parse_move(n: u16, sign: bool, text: &str) -> Result<(Move, usize), &'static
             };
 
             if wide {
-                return Err("invalid notation: lowercase moves cannot be marked wide");
+                return error(
+                    ParseErrorKind::InvalidLayers,
+                    Some("lowercase moves cannot be marked wide"),
+                );
             }
 
-            if sign || n == 3 {
-                let width = start.unwrap_or(2);
-                if width > n {
-                    return Err("move too big");
-                }
-                Move {
-                    n,
-                    face,
-                    start: 0,
-                    end: width,
-                    count,
-                }
-            } else if let Some(start) = start {
-                if start > n {
-                    return Err("invalid notation: move too big");
-                }
-
-                let start = start
-                    .checked_sub(1)
-                    .ok_or("invalid notation: invalid start index")?;
-                let end = end.unwrap_or(start + 1);
-
-                Move {
-                    n,
-                    face,
-                    start,
-                    end,
-                    count,
-                }
-            } else {
-                Move {
-                    n,
-                    face,
-                    start: 1,
-                    end: 2,
-                    count,
-                }
+            let (start, end) = match (start, end) {
+                (first, Some(last)) => layers(n, first.unwrap_or(0), last, 0..len)?,
+                (width, None) if sign || n == 3 => layers(n, 1, width.unwrap_or(2), 0..len)?,
+                (Some(layer), None) => layers(n, layer, layer, 0..len)?,
+                (None, None) => (1, 2),
+            };
+            Move {
+                n,
+                face,
+                start,
+                end,
+                count,
             }
         }
         'm' | 'e' | 's' => {
             if n < 5 {
-                return Err(
-                    "invalid notation: inner middle slice moves are not valid on cubes smaller than 5x5x5",
+                return error(
+                    ParseErrorKind::NoInnerSlice,
+                    Some("inner slice moves need at least a 5x5x5"),
                 );
             }
 
@@ -818,19 +1576,28 @@ fn parse_move(n: u16, sign: bool, text: &str) -> Result<(Move, usize), &'static
             };
 
             if start.is_some() || end.is_some() {
-                return Err("invalid notation: slice moves cannot have layer specifiers");
+                return error(
+                    ParseErrorKind::InvalidLayers,
+                    Some("slice moves cannot have layer specifiers"),
+                );
             }
 
             if wide {
-                return Err("invalid notation: slice moves cannot be wide");
+                return error(
+                    ParseErrorKind::InvalidLayers,
+                    Some("slice moves cannot be wide"),
+                );
             }
 
             if n % 2 == 0 {
-                return Err("invalid notation: cannot use inner slice moves on even-layered cubes");
+                return error(
+                    ParseErrorKind::NoInnerSlice,
+                    Some("even-layered cubes have no inner slice"),
+                );
             }
 
             if n / 2 + 1 > n {
-                return Err("move too big");
+                return error(ParseErrorKind::MoveTooBig, None);
             }
 
             Move {
@@ -850,11 +1617,17 @@ fn parse_move(n: u16, sign: bool, text: &str) -> Result<(Move, usize), &'static
             };
 
             if start.is_some() || end.is_some() {
-                return Err("invalid notation: slice moves cannot have layer specifiers");
+                return error(
+                    ParseErrorKind::InvalidLayers,
+                    Some("slice moves cannot have layer specifiers"),
+                );
             }
 
             if wide {
-                return Err("invalid notation: slice moves cannot be wide");
+                return error(
+                    ParseErrorKind::InvalidLayers,
+                    Some("slice moves cannot be wide"),
+                );
             }
 
             Move {
@@ -874,11 +1647,17 @@ fn parse_move(n: u16, sign: bool, text: &str) -> Result<(Move, usize), &'static
             };
 
             if start.is_some() || end.is_some() {
-                return Err("invalid notation: rotations cannot have layer specifiers");
+                return error(
+                    ParseErrorKind::InvalidLayers,
+                    Some("rotations cannot have layer specifiers"),
+                );
             }
 
             if wide {
-                return Err("invalid notation: rotations cannot be wide");
+                return error(
+                    ParseErrorKind::InvalidLayers,
+                    Some("rotations cannot be wide"),
+                );
             }
 
             Move {
@@ -890,14 +1669,11 @@ fn parse_move(n: u16, sign: bool, text: &str) -> Result<(Move, usize), &'static
             }
         }
         _ => {
-            return Err("invalid notation");
+            return error(ParseErrorKind::UnknownMove(letter), None);
         }
     };
 
-    match chars.next() {
-        Some((i, _)) => Ok((mv, i)),
-        None => Ok((mv, text.len())),
-    }
+    Ok((mv, len))
 }
 
 pub fn format_moves(standard_moves: &[Move]) -> String {
@@ -911,6 +1687,36 @@ pub fn format_moves(standard_moves: &[Move]) -> String {
     s
 }
 
+/// Formats `moves` in the style of `options`.
+///
+/// To write the moves without rotations, pass them through
+/// [`remove_rotations`] first.
+pub fn format_moves_with(moves: &[Move], options: &FormatOptions) -> String {
+    let mut s = String::new();
+    let tokens = moves.iter().copied().map(Token::Move).collect::<Vec<_>>();
+    format_tokens_with(&mut s, tokens, options).unwrap();
+    s
+}
+
+/// Drops the rotations of `moves` and turns the moves after each rotation
+/// instead, so the result does the same to the cube up to its orientation.
+pub fn remove_rotations(moves: &[Move]) -> Vec<Move> {
+    let mut orientation = EdgeSticker::Uf;
+    let mut result = Vec::new();
+    for &mv in moves {
+        let mv = Move {
+            face: map_orientation(orientation, mv.face),
+            ..mv
+        };
+        if mv.is_rotation() {
+            orientation = rotate_orientation(orientation, mv);
+        } else {
+            result.push(mv);
+        }
+    }
+    result
+}
+
 pub fn rotate_from(n: u16, orientation: EdgeSticker) -> Vec<Move> {
     let mut moves = Vec::new();
     let (x, y, z) = orientation.xyz();
@@ -969,7 +1775,7 @@ mod tests {
     #[test]
     fn parse() {
         fn check(n: u16, text: &str, expected: Expect) {
-            let (mv, len) = parse_move(n, false, text).unwrap();
+            let (mv, len) = parse_move(n, ParseMode::Wca, text, &mut Vec::new()).unwrap();
             let dbg = format!(
                 "{:?}[{}-{}]{}|{}",
                 mv.face,
@@ -1009,6 +1815,32 @@ mod tests {
         check(6, "r", expect!["R[1-2]|"]);
         check(6, "2r", expect!["R[1-2]|"]);
         check(6, "3r", expect!["R[2-3]|"]);
+
+        check(6, "3R", expect!["R[2-3]|"]);
+        check(6, "3Rw", expect!["R[0-3]|"]);
+        check(6, "2-4Rw", expect!["R[1-4]|"]);
+        check(6, "2-3L'", expect!["L[1-3]'|"]);
+        check(7, "3-5r2", expect!["R[2-5]2|"]);
+        check(7, "1-7u", expect!["U[0-7]|"]);
+    }
+
+    #[test]
+    fn layer_ranges() {
+        #[track_caller]
+        fn check(n: u16, alg: &str, expected: Expect) {
+            let tree = parse_alg(n, ParseMode::Wca, alg).unwrap();
+            let formatted = format!("{tree}");
+            let reparsed = parse_alg(n, ParseMode::Wca, &formatted).unwrap();
+            assert_eq!(reparsed.to_moves(), tree.to_moves());
+            expected.assert_eq(&formatted);
+        }
+
+        check(
+            6,
+            "2-4Rw 2-3L' 3-5r2 3R 4U",
+            expect!["2-4r 2-3l' 3-5r2 3r 4u"],
+        );
+        check(7, "2-6f 1-2B 1-3D'", expect!["S Bw 3Dw'"]);
     }
 
     #[test]
@@ -1673,4 +2505,315 @@ mod tests {
             expect!["R' D U R2 U' R' D' R U R' D R' D' U' R"],
         );
     }
+
+    #[test]
+    fn parse_errors() {
+        #[track_caller]
+        fn check(n: u16, alg: &str, expected: Expect) {
+            let error = parse_alg(n, ParseMode::Wca, alg).unwrap_err();
+            expected.assert_eq(&format!("{error}"));
+        }
+
+        check(
+            3,
+            "R U [R, U",
+            expect!["unclosed '[' at 4..5: expected ']'"],
+        );
+        check(3, "R U] R'", expect!["unexpected ']' at 3..4"]);
+        check(3, "R (U R2", expect!["unclosed '(' at 2..3: expected ')'"]);
+        check(3, "R 4Rw U", expect!["move too big at 2..5"]);
+        check(
+            3,
+            "R U 2x'",
+            expect!["invalid layers at 4..7: rotations cannot have layer specifiers"],
+        );
+        check(3, "R Q2", expect!["unknown move 'Q' at 2..4"]);
+        check(
+            3,
+            "R m",
+            expect!["no inner slice at 2..3: inner slice moves need at least a 5x5x5"],
+        );
+        check(
+            6,
+            "m",
+            expect!["no inner slice at 0..1: even-layered cubes have no inner slice"],
+        );
+        check(
+            4,
+            "R 0r",
+            expect!["invalid layers at 2..4: layers are counted from 1"],
+        );
+        check(4, "R U999", expect!["count too big at 2..6"]);
+        check(4, "R 2-5Rw", expect!["move too big at 2..7"]);
+        check(
+            4,
+            "R 3-2Rw",
+            expect!["invalid layers at 2..7: layer ranges go from the outer to the inner layer"],
+        );
+        check(4, "R 3", expect!["missing move letter at 2..3"]);
+        check(3, "R’ Ü", expect!["unknown move 'Ü' at 5..7"]);
+    }
+
+    #[test]
+    fn sign() {
+        #[track_caller]
+        fn check(n: u16, alg: &str, expected: Expect) {
+            let tree = parse_alg(n, ParseMode::Sign, alg).unwrap();
+            let mut wca = String::new();
+            format_tokens(&mut wca, tree.to_tokens()).unwrap();
+            let mut sign = String::new();
+            format_tokens_in(&mut sign, tree.to_tokens(), ParseMode::Sign).unwrap();
+            let mut expected_cube = Cube::new_solved(n);
+            tree.apply_to(&mut expected_cube);
+            let mut cube = Cube::new_solved(n);
+            let reparsed = parse_alg(n, ParseMode::Sign, &sign).unwrap();
+            reparsed.apply_to(&mut cube);
+            assert_eq!(cube, expected_cube);
+            expected.assert_eq(&format!("{wca} | {sign}"));
+        }
+
+        check(3, "r U R' u' M2", expect!["Rw U R' Uw' M2 | r U R' u' M2"]);
+        check(3, "X Y' Z2 x", expect!["x y' z2 x | x y' z2 x"]);
+        check(
+            5,
+            "r 3r' Rw 2R 3R2 2-3r 2-4r'",
+            expect!["Rw 3Rw' Rw r m2 2-3r M | r 3r' r 2R 3R2 2-3r M"],
+        );
+        check(
+            7,
+            "[3r, U] 4R' 2-6r",
+            expect!["[3Rw, U] m M' | [3r, U] 4R' M'"],
+        );
+
+        let error = parse_alg(4, ParseMode::Sign, "5R").unwrap_err();
+        expect!["move too big at 0..2"].assert_eq(&format!("{error}"));
+        let error = parse_alg(4, ParseMode::Sign, "3-2r").unwrap_err();
+        expect!["invalid layers at 0..4: layer ranges go from the outer to the inner layer"]
+            .assert_eq(&format!("{error}"));
+    }
+
+    #[test]
+    fn format_options() {
+        #[track_caller]
+        fn check(n: u16, alg: &str, options: FormatOptions, expected: Expect) {
+            let moves = parse_alg(n, ParseMode::Wca, alg).unwrap().to_moves();
+            let formatted = format_moves_with(&moves, &options);
+            if options.prime == '\'' {
+                let mode = match options.wide {
+                    WideStyle::Suffix => ParseMode::Wca,
+                    WideStyle::Lowercase => ParseMode::Sign,
+                };
+                let reparsed = parse_alg(n, mode, &formatted).unwrap().to_moves();
+                let cancel = |moves: &[Move]| {
+                    let mut canceler = Canceler::new();
+                    canceler.extend(moves.iter().copied());
+                    canceler.moves
+                };
+                assert_eq!(cancel(&reparsed), cancel(&moves));
+            }
+            expected.assert_eq(&formatted);
+        }
+
+        let wca = FormatOptions::default();
+        let sign = FormatOptions::from(ParseMode::Sign);
+        let no_slices = FormatOptions {
+            slices: false,
+            ..wca
+        };
+        let house = FormatOptions {
+            prime: '’',
+            double_prime: true,
+            ..wca
+        };
+
+        check(3, "M' U r E2 S x'", wca, expect!["M' U Rw E2 S x'"]);
+        check(
+            3,
+            "M' U r E2 S x'",
+            no_slices,
+            expect!["Lw' L U Rw Dw2 D2 Fw F' x'"],
+        );
+        check(
+            5,
+            "3Rw' r 3r 2-3r' m",
+            no_slices,
+            expect!["3Rw' Rw R' 3Rw Rw' 3Rw' R 3Lw Lw'"],
+        );
+        check(6, "3Rw r 3r' 2-4Lw", sign, expect!["3r 2R 3R' 2-4l"]);
+        check(3, "R' U' M2 x2 (R U)'", house, expect!["R’ U’ M2 x2 U’ R’"]);
+
+        let moves = parse_alg(3, ParseMode::Wca, "x R U y R' z' F2")
+            .unwrap()
+            .to_moves();
+        expect!["R F U' R2"].assert_eq(&format_moves_with(&remove_rotations(&moves), &wca));
+        let tree = parse_alg(3, ParseMode::Wca, "[M2, U'] (R2 U)2").unwrap();
+        let mut formatted = String::new();
+        format_tokens_with(&mut formatted, tree.mirror(Axis::X).to_tokens(), &house).unwrap();
+        expect!["[M2’, U] (L2 U’)2"].assert_eq(&formatted);
+
+        let mut formatted = String::new();
+        let half_turns = [(Face::R, 1, 2), (Face::L, 0, 3), (Face::R, 0, 1)];
+        for (face, start, end) in half_turns {
+            let mv = Move {
+                n: 3,
+                face,
+                start,
+                end,
+                count: 2,
+            };
+            if !formatted.is_empty() {
+                formatted.push(' ');
+            }
+            format_move_with(&mut formatted, mv, &house).unwrap();
+        }
+        expect!["M2’ x2’ R2"].assert_eq(&formatted);
+    }
+
+    #[test]
+    fn lenient() {
+        #[track_caller]
+        fn check(alg: &str, expected: Expect) {
+            let (tree, warnings) = parse_alg_with_warnings(3, ParseMode::Lenient, alg).unwrap();
+            let mut actual = format!("{tree}");
+            for warning in warnings {
+                actual += &format!(" | {warning}");
+            }
+            expected.assert_eq(&actual);
+        }
+
+        check("R U R' U'", expect!["R U R' U'"]);
+        check(
+            "R'2 U2' Rw2'  U’",
+            expect!["R2 U2 Rw2 U' | count after prime at 0..3"],
+        );
+        check(
+            "R U´ R‘",
+            expect!["R U' R' | '´' used as prime at 3..5 | '‘' used as prime at 7..10"],
+        );
+        check(
+            "*X\tZ2",
+            expect![[
+                r#"x z2 | stray '*' at 0..1 | uppercase rotation 'X' at 1..2 | unusual whitespace '\t' at 2..3 | uppercase rotation 'Z' at 3..4"#
+            ]],
+        );
+        check("[R'3, U]", expect!["[R, U] | count after prime at 1..4"]);
+
+        let error = parse_alg(3, ParseMode::Wca, "R'2").unwrap_err();
+        expect!["missing move letter at 2..3"].assert_eq(&format!("{error}"));
+        let error = parse_alg(3, ParseMode::Wca, "R * U").unwrap_err();
+        expect!["unknown move '*' at 2..3"].assert_eq(&format!("{error}"));
+        let error = parse_alg(3, ParseMode::Lenient, "R Q").unwrap_err();
+        expect!["unknown move 'Q' at 2..3"].assert_eq(&format!("{error}"));
+    }
+
+    #[test]
+    fn repeat() {
+        #[track_caller]
+        fn check(alg: &str, expected: Expect) {
+            let tree = parse_alg(3, ParseMode::Wca, alg).unwrap();
+            let inverse = tree.to_inverse_moves();
+            let reversed = tree.to_moves().into_iter().rev().map(|mv| mv.inverse());
+            assert_eq!(inverse, reversed.collect::<Vec<_>>());
+            let inverse = format_moves(&inverse);
+            let moves = format_moves(&tree.to_moves());
+            expected.assert_eq(&format!("{tree} | {moves} | {inverse}"));
+        }
+
+        check(
+            "(R U R' U')3",
+            expect!["(R U R' U')3 | R U R' U' R U R' U' R U R' U' | U R U' R' U R U' R' U R U' R'"],
+        );
+        check(
+            "[R, U]2 D",
+            expect!["[R, U]2 D | R U R' U' R U R' U' D | D' U R U' R' U R U' R'"],
+        );
+        check("(R U)'", expect!["(R U)' | U' R' | R U"]);
+        check(
+            "[M2 / U]' R",
+            expect!["[M2 / U]' R | M2 U M2 M2 U' M2 R | R' M2 U M2 M2 U' M2"],
+        );
+        check("(R [U: F]2')2 {R}", expect!["(R [U: F]2')2 R | R U F' U' U F' U' R U F' U' U F' U' R | R' U F U' U F U' R' U F U' U F U' R'"]);
+
+        let error = parse_alg(3, ParseMode::Wca, "(R U)99999").unwrap_err();
+        expect!["count too big at 5..10"].assert_eq(&format!("{error}"));
+        let error = parse_alg(3, ParseMode::Wca, "(R U) 2").unwrap_err();
+        expect!["missing move letter at 6..7"].assert_eq(&format!("{error}"));
+    }
+
+    #[test]
+    fn move_set() {
+        #[track_caller]
+        fn check(alg: &str, expected: Expect) {
+            let tree = parse_alg(3, ParseMode::Wca, alg).unwrap();
+            let moves = format_moves(&tree.to_moves());
+            expected.assert_eq(&format!("{tree} | {moves}"));
+        }
+
+        check("<R,U> R U R' U", expect!["<R, U> R U R' U | R U R' U"]);
+        check(
+            "R <R U2 F'> [<M, U>: U]",
+            expect!["R <R, U2, F'> [<M, U>: U] | R U"],
+        );
+        check("<>", expect!["<> | "]);
+
+        let error = parse_alg(3, ParseMode::Wca, "<R, U").unwrap_err();
+        expect!["unclosed '<' at 0..1: expected '>'"].assert_eq(&format!("{error}"));
+        let error = parse_alg(3, ParseMode::Wca, "<R, [U]>").unwrap_err();
+        expect!["unexpected '[' at 4..5: move sets can only contain moves"]
+            .assert_eq(&format!("{error}"));
+        let error = parse_alg(3, ParseMode::Wca, "R >").unwrap_err();
+        expect!["unexpected '>' at 2..3"].assert_eq(&format!("{error}"));
+    }
+
+    #[test]
+    fn transformations() {
+        #[track_caller]
+        fn check(n: u16, alg: &str, expected: Expect) {
+            let tree = parse_alg(n, ParseMode::Wca, alg).unwrap();
+
+            let inverse = tree.inverse();
+            assert_eq!(inverse.to_moves(), tree.to_inverse_moves());
+            assert_eq!(inverse.inverse(), tree);
+
+            let rotation = EdgeSticker::Rb;
+            let transformed = tree.transform(rotation);
+            let apply = |moves: Vec<Move>| {
+                let mut cube = Cube::new_solved(n);
+                let mut rotated = RotatedCube::new(&mut cube);
+                for mv in moves {
+                    rotated.rotate(mv.face, mv.start..mv.end, mv.count);
+                }
+                cube
+            };
+            let mut expected_moves = rotate_from(n, rotation);
+            expected_moves.extend(tree.to_moves());
+            let mut moves = transformed.to_moves();
+            moves.extend(rotate_from(n, rotation));
+            assert!(apply(moves) == apply(expected_moves));
+
+            let mirror = tree.mirror(Axis::X);
+            assert_eq!(mirror.mirror(Axis::X), tree);
+            expected.assert_eq(&format!(
+                "{inverse} | {transformed} | {mirror} | {}",
+                tree.mirror(Axis::Y)
+            ));
+        }
+
+        check(
+            3,
+            "[R U R', D]",
+            expect!["[D, R U R'] | [U R U', L] | [L' U' L, D'] | [R' D' R, U']"],
+        );
+        check(3, "[F: [R, U]] (R U2)2' x", expect!["x' (R U2)2 [F: [U, R]] | [B: [U, R]] (U R2)2' y | [F': [L', U']] (L' U2)2' x | [F': [R', D']] (R' D2)2' x'"]);
+        check(
+            3,
+            "[M2 / U] <R, U>",
+            expect!["<R, U> [M2 / U] | [E2 / R] <U, R> | [M2 / U'] <L', U'> | [M2 / D'] <R', D'>"],
+        );
+        check(
+            5,
+            "[3Rw: [r, U']]",
+            expect!["[3Rw: [U', r]] | [3Uw: [u, R']] | [3Lw': [l', U]] | [3Rw': [r', D]]"],
+        );
+    }
 }
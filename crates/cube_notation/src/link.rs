@@ -0,0 +1,340 @@
+use crate::{format_tokens_in, parse_alg, Delimiter, ParseError, ParseMode, Tree};
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+use core::fmt::{self, Display, Write};
+
+/// A site that plays algs from links.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlgSite {
+    /// `alg.cubing.net`, which writes spaces as `_` and primes as `-`.
+    AlgCubingNet,
+    /// The Twizzle editor, which uses the usual query encoding.
+    Twizzle,
+}
+
+/// An alg to play on an `n` by `n` cube, after an optional setup.
+///
+/// The sites read algs in SiGN notation, so links are parsed and formatted
+/// in [`ParseMode::Sign`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlgLink {
+    pub n: u16,
+    pub alg: Tree,
+    pub setup: Option<Tree>,
+}
+
+/// An error in a link to an alg.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkError {
+    /// A `%` escape that is not two hex digits or not UTF-8.
+    InvalidEscape,
+    /// A puzzle other than a cube, like `megaminx`.
+    UnknownPuzzle(String),
+    /// An error in the alg, with a span in the decoded alg.
+    Alg(ParseError),
+    /// An error in the setup, with a span in the decoded setup.
+    Setup(ParseError),
+}
+
+impl Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkError::InvalidEscape => f.write_str("invalid escape"),
+            LinkError::UnknownPuzzle(puzzle) => write!(f, "unknown puzzle '{}'", puzzle),
+            LinkError::Alg(e) => write!(f, "{} in alg", e),
+            LinkError::Setup(e) => write!(f, "{} in setup", e),
+        }
+    }
+}
+
+impl core::error::Error for LinkError {}
+
+/// Writes `text` percent-encoded, leaving the bytes of `unescaped` as they
+/// are and writing spaces as `+` if `plus` is set.
+fn encode(out: &mut String, text: &str, unescaped: &str, plus: bool) {
+    for &b in text.as_bytes() {
+        if b.is_ascii_alphanumeric() || unescaped.as_bytes().contains(&b) {
+            out.push(char::from(b));
+        } else if b == b' ' && plus {
+            out.push('+');
+        } else {
+            write!(out, "%{:02X}", b).unwrap();
+        }
+    }
+}
+
+fn decode(text: &str) -> Result<String, LinkError> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&b, after)) = rest.split_first() {
+        rest = after;
+        match b {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex = rest
+                    .get(..2)
+                    .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                    .and_then(|hex| core::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .ok_or(LinkError::InvalidEscape)?;
+                bytes.push(hex);
+                rest = &rest[2..];
+            }
+            b => bytes.push(b),
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| LinkError::InvalidEscape)
+}
+
+/// Returns the tree with what the sites cannot read taken out: macros and
+/// NISS parts are replaced by their algs, and move sets and insertions,
+/// which have no moves, are dropped.
+fn playable(tree: &Tree) -> Tree {
+    let map = |tree: &Tree| Box::new(playable(tree));
+    match tree {
+        &Tree::Move(mv) => Tree::Move(mv),
+        Tree::Group(delim, trees) => Tree::Group(
+            *delim,
+            trees
+                .iter()
+                .filter(|tree| !matches!(tree, Tree::MoveSet(_) | Tree::Insertion))
+                .map(playable)
+                .collect(),
+        ),
+        Tree::Conj(delim, a, b) => Tree::Conj(*delim, map(a), map(b)),
+        Tree::Comm(delim, a, b) => Tree::Comm(*delim, map(a), map(b)),
+        Tree::Slash(delim, a, b) => Tree::Slash(*delim, map(a), map(b)),
+        Tree::Repeat(tree, count) => Tree::Repeat(map(tree), *count),
+        Tree::MoveSet(_) | Tree::Insertion => Tree::Group(Delimiter::None, vec![]),
+        Tree::Macro(_, tree) | Tree::Niss(tree) => playable(tree),
+    }
+}
+
+/// Formats `tree` in SiGN notation, escaped for `site`.
+fn encode_alg(out: &mut String, site: AlgSite, tree: &Tree) {
+    let mut alg = String::new();
+    format_tokens_in(&mut alg, playable(tree).to_tokens(), ParseMode::Sign).unwrap();
+    match site {
+        AlgSite::AlgCubingNet => {
+            let alg = alg
+                .replace('_', "&#95;")
+                .replace(' ', "_")
+                .replace('+', "&#2b;")
+                .replace('-', "&#45;")
+                .replace('\'', "-");
+            encode(out, &alg, "-_.!~*()@:$,;", false);
+        }
+        AlgSite::Twizzle => encode(out, &alg, "-_.*", true),
+    }
+}
+
+impl AlgLink {
+    /// Returns a link that plays the alg on `site`. Macros and NISS parts
+    /// are written out as their moves, and move sets and insertions, which
+    /// the sites cannot read, are left out.
+    pub fn to_url(&self, site: AlgSite) -> String {
+        let (mut url, setup_key) = match site {
+            AlgSite::AlgCubingNet => (String::from("https://alg.cubing.net/?"), "setup"),
+            AlgSite::Twizzle => (
+                String::from("https://alpha.twizzle.net/edit/?"),
+                "setup-alg",
+            ),
+        };
+        if let Some(setup) = &self.setup {
+            url += setup_key;
+            url.push('=');
+            encode_alg(&mut url, site, setup);
+            url.push('&');
+        }
+        url += "alg=";
+        encode_alg(&mut url, site, &self.alg);
+        if self.n != 3 {
+            write!(url, "&puzzle={0}x{0}x{0}", self.n).unwrap();
+        }
+        url
+    }
+}
+
+/// Parses a link made by [`AlgLink::to_url`] or by one of the sites.
+///
+/// The site is recognized from the host, and any parameters other than the
+/// alg, the setup and the puzzle are ignored.
+pub fn parse_alg_link(url: &str) -> Result<AlgLink, LinkError> {
+    let url = url.split('#').next().unwrap_or_default();
+    let (address, query) = url.split_once('?').unwrap_or((url, ""));
+    let site = if address.contains("alg.cubing.net") {
+        AlgSite::AlgCubingNet
+    } else {
+        AlgSite::Twizzle
+    };
+
+    let mut n = 3;
+    let mut alg = String::new();
+    let mut setup = None;
+    for param in query.split('&') {
+        let (key, value) = param.split_once('=').unwrap_or((param, ""));
+        let mut value = decode(value)?;
+        if site == AlgSite::AlgCubingNet {
+            value = value
+                .replace('_', " ")
+                .replace('-', "'")
+                .replace("&#95;", "_")
+                .replace("&#45;", "-")
+                .replace("&#2b;", "+");
+        }
+        match key {
+            "alg" => alg = value,
+            "setup" | "setup-alg" => setup = Some(value),
+            "puzzle" => {
+                let sizes = value.split('x').collect::<Vec<_>>();
+                n = match sizes[..] {
+                    [a, b, c] if a == b && b == c => a.parse().ok().filter(|&n| n >= 2),
+                    _ => None,
+                }
+                .ok_or_else(|| LinkError::UnknownPuzzle(value.clone()))?;
+            }
+            _ => {}
+        }
+    }
+
+    let setup = setup
+        .map(|setup| parse_alg(n, ParseMode::Sign, &setup).map_err(LinkError::Setup))
+        .transpose()?;
+    let alg = parse_alg(n, ParseMode::Sign, &alg).map_err(LinkError::Alg)?;
+    Ok(AlgLink { n, alg, setup })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_alg_with_definitions, Definitions};
+    use alloc::{format, string::ToString};
+    use expect_test::{expect, Expect};
+
+    #[test]
+    fn to_url() {
+        #[track_caller]
+        fn check(n: u16, alg: &str, setup: Option<&str>, expected: Expect) {
+            let link = AlgLink {
+                n,
+                alg: parse_alg(n, ParseMode::Sign, alg).unwrap(),
+                setup: setup.map(|setup| parse_alg(n, ParseMode::Sign, setup).unwrap()),
+            };
+            let urls = [AlgSite::AlgCubingNet, AlgSite::Twizzle].map(|site| link.to_url(site));
+            for url in &urls {
+                assert_eq!(parse_alg_link(url).unwrap(), link);
+            }
+            expected.assert_eq(&urls.join("\n"));
+        }
+
+        check(
+            3,
+            "R U R' U'",
+            None,
+            expect![[r#"
+                https://alg.cubing.net/?alg=R_U_R-_U-
+                https://alpha.twizzle.net/edit/?alg=R+U+R%27+U%27"#]],
+        );
+        check(
+            3,
+            "[R' D R, U2]",
+            Some("y'"),
+            expect![[r#"
+                https://alg.cubing.net/?setup=y-&alg=%5BR-_D_R,_U2%5D
+                https://alpha.twizzle.net/edit/?setup-alg=y%27&alg=%5BR%27+D+R%2C+U2%5D"#]],
+        );
+        check(
+            5,
+            "[3r: [M', U]] (2-3l)2",
+            None,
+            expect![[r#"
+                https://alg.cubing.net/?alg=%5B3r:_%5BM-,_U%5D%5D_(2%26%2345;3l)2&puzzle=5x5x5
+                https://alpha.twizzle.net/edit/?alg=%5B3r%3A+%5BM%27%2C+U%5D%5D+%282-3l%292&puzzle=5x5x5"#]],
+        );
+    }
+
+    #[test]
+    fn playable_url() {
+        #[track_caller]
+        fn check(mode: ParseMode, alg: &str, expected: Expect) {
+            let definitions = Definitions::parse(3, ParseMode::Sign, "sexy = R U R' U'").unwrap();
+            let link = AlgLink {
+                n: 3,
+                alg: parse_alg_with_definitions(3, mode, alg, &definitions).unwrap(),
+                setup: None,
+            };
+            let urls = [AlgSite::AlgCubingNet, AlgSite::Twizzle].map(|site| link.to_url(site));
+            for (url, site) in urls.iter().zip([AlgSite::AlgCubingNet, AlgSite::Twizzle]) {
+                let parsed = parse_alg_link(url).unwrap();
+                assert_eq!(parsed.alg.to_moves(), link.alg.to_moves());
+                assert_eq!(&parsed.to_url(site), url);
+            }
+            expected.assert_eq(&urls.join("\n"));
+        }
+
+        check(
+            ParseMode::Sign,
+            "[sexy, D]",
+            expect![[r#"
+                https://alg.cubing.net/?alg=%5BR_U_R-_U-,_D%5D
+                https://alpha.twizzle.net/edit/?alg=%5BR+U+R%27+U%27%2C+D%5D"#]],
+        );
+        check(
+            ParseMode::Sign,
+            "<R, U> R U2 R'",
+            expect![[r#"
+                https://alg.cubing.net/?alg=R_U2_R-
+                https://alpha.twizzle.net/edit/?alg=R+U2+R%27"#]],
+        );
+        check(
+            ParseMode::Fmc,
+            "R ^ (U) sexy ^",
+            expect![[r#"
+                https://alg.cubing.net/?alg=R_U_R_U_R-_U-
+                https://alpha.twizzle.net/edit/?alg=R+U+R+U+R%27+U%27"#]],
+        );
+    }
+
+    #[test]
+    fn parse() {
+        #[track_caller]
+        fn check(url: &str, expected: Expect) {
+            let actual = match parse_alg_link(url) {
+                Ok(link) => {
+                    let mut actual = format!("{}x{} {:?}", link.n, link.n, link.alg.to_string());
+                    if let Some(setup) = link.setup {
+                        actual += &format!(" setup {:?}", setup.to_string());
+                    }
+                    actual
+                }
+                Err(e) => format!("{e}"),
+            };
+            expected.assert_eq(&actual);
+        }
+
+        check(
+            "https://alg.cubing.net/?alg=%5BR,_U%5D_x-&type=alg&view=playback",
+            expect![[r#"3x3 "[R, U] x'""#]],
+        );
+        check(
+            "https://alpha.twizzle.net/edit/?puzzle=4x4x4&alg=r+U2+r%27&setup-alg=Rw2#top",
+            expect![[r#"4x4 "Rw U2 Rw'" setup "Rw2""#]],
+        );
+        check("https://alg.cubing.net/", expect![[r#"3x3 """#]]);
+        check(
+            "https://alpha.twizzle.net/edit/?puzzle=megaminx",
+            expect!["unknown puzzle 'megaminx'"],
+        );
+        check(
+            "https://alpha.twizzle.net/edit/?alg=R%2",
+            expect!["invalid escape"],
+        );
+        check(
+            "https://alpha.twizzle.net/edit/?alg=R%+1",
+            expect!["invalid escape"],
+        );
+        check(
+            "https://alg.cubing.net/?alg=R_U_%5D",
+            expect!["unexpected ']' at 4..5 in alg"],
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{
+    commutator::{axis_run, simplify},
+    rotate_orientation, Canceler, Move, Tree,
+};
+use alloc::vec::Vec;
+use cube::{map_orientation, EdgeSticker};
+
+/// A way of counting the moves of an alg.
+///
+/// Except for [`Metric::Etm`], rotations are free and moves are counted
+/// after cancelling them, so `R R` is one move and `R L' x'` is two moves in
+/// HTM and STM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// Half turn metric: every turn of a block of outer layers counts once,
+    /// so slices count as two moves.
+    Htm,
+    /// Quarter turn metric: like HTM, but half turns count twice.
+    Qtm,
+    /// Slice turn metric: every turn of a contiguous block of layers counts
+    /// once, including slices.
+    Stm,
+    /// Execution turn metric: every move as written counts once, including
+    /// rotations, after merging repeated moves like `R R'`.
+    Etm,
+    /// Axial turn metric: every run of moves about the same axis counts
+    /// once, since they can be done at the same time.
+    Atm,
+}
+
+/// Returns the number of block moves left in `moves` after taking out
+/// rotations and merging the moves about one axis that turn the same
+/// layers. Moves on different layers are not merged, so `R L'` stays two
+/// moves rather than becoming `M' x`.
+fn block_moves(moves: &[Move]) -> usize {
+    let mut orientation = EdgeSticker::Uf;
+    // The blocks turned by the last run of moves about `axis`, as their
+    // layers counted from the R, U or F side and their quarter turns.
+    let mut axis = None;
+    let mut blocks: Vec<(usize, usize, u8)> = Vec::new();
+    let mut count = 0;
+    for &mv in moves {
+        if mv.is_rotation() {
+            orientation = rotate_orientation(orientation, mv);
+            continue;
+        }
+        let face = map_orientation(orientation, mv.face);
+        let (mv_axis, layers) = axis_run(Move { face, ..mv });
+        let Some(start) = layers.iter().position(|&count| count != 0) else {
+            continue;
+        };
+        let end = layers.iter().rposition(|&count| count != 0).unwrap() + 1;
+        let turns = layers[start];
+
+        if axis != Some(mv_axis) {
+            count += blocks.iter().filter(|block| block.2 != 0).count();
+            blocks.clear();
+            axis = Some(mv_axis);
+        }
+        match blocks
+            .iter_mut()
+            .find(|block| (block.0, block.1) == (start, end))
+        {
+            Some(block) => block.2 = (block.2 + turns) % 4,
+            None => blocks.push((start, end, turns)),
+        }
+    }
+    count + blocks.iter().filter(|block| block.2 != 0).count()
+}
+
+fn cancel(moves: &[Move]) -> Vec<Move> {
+    let mut canceler = Canceler::new();
+    canceler.extend(moves.iter().copied());
+    canceler.moves
+}
+
+/// Returns the number of moves of `moves` in `metric`.
+pub fn move_count(moves: &[Move], metric: Metric) -> usize {
+    let same_axis = |a: &Move, b: &Move| axis_run(*a).0 == axis_run(*b).0;
+    match metric {
+        Metric::Htm => cancel(moves).len(),
+        Metric::Qtm => cancel(moves)
+            .iter()
+            .map(|mv| if mv.count % 4 == 2 { 2 } else { 1 })
+            .sum(),
+        Metric::Stm => block_moves(moves),
+        Metric::Etm => simplify(moves.iter().copied()).len(),
+        Metric::Atm => cancel(moves).chunk_by(same_axis).count(),
+    }
+}
+
+impl Tree {
+    /// Returns the number of moves of the tree in `metric`.
+    pub fn move_count(&self, metric: Metric) -> usize {
+        move_count(&self.to_moves(), metric)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_alg, ParseMode};
+    use alloc::{format, string::String};
+    use expect_test::{expect, Expect};
+
+    #[test]
+    fn metrics() {
+        #[track_caller]
+        fn check(n: u16, alg: &str, expected: Expect) {
+            let tree = parse_alg(n, ParseMode::Wca, alg).unwrap();
+            let mut actual = String::new();
+            for (name, metric) in [
+                ("HTM", Metric::Htm),
+                ("QTM", Metric::Qtm),
+                ("STM", Metric::Stm),
+                ("ETM", Metric::Etm),
+                ("ATM", Metric::Atm),
+            ] {
+                if !actual.is_empty() {
+                    actual.push(' ');
+                }
+                actual += &format!("{name} {}", tree.move_count(metric));
+            }
+            expected.assert_eq(&actual);
+        }
+
+        check(3, "", expect!["HTM 0 QTM 0 STM 0 ETM 0 ATM 0"]);
+        check(3, "R U R' U'", expect!["HTM 4 QTM 4 STM 4 ETM 4 ATM 4"]);
+        check(3, "R U2 R'", expect!["HTM 3 QTM 4 STM 3 ETM 3 ATM 3"]);
+        check(3, "R R", expect!["HTM 1 QTM 2 STM 1 ETM 1 ATM 1"]);
+        check(3, "R R'", expect!["HTM 0 QTM 0 STM 0 ETM 0 ATM 0"]);
+        check(3, "x y2", expect!["HTM 0 QTM 0 STM 0 ETM 2 ATM 0"]);
+        check(
+            3,
+            "M2 U M2 U2 M2 U M2",
+            expect!["HTM 11 QTM 20 STM 7 ETM 7 ATM 7"],
+        );
+        check(3, "R L' x'", expect!["HTM 2 QTM 2 STM 2 ETM 3 ATM 1"]);
+        check(3, "R L", expect!["HTM 2 QTM 2 STM 2 ETM 2 ATM 1"]);
+        check(3, "R2 L2", expect!["HTM 2 QTM 4 STM 2 ETM 2 ATM 1"]);
+        check(3, "M x M' x'", expect!["HTM 0 QTM 0 STM 0 ETM 4 ATM 0"]);
+        check(3, "x R", expect!["HTM 1 QTM 1 STM 1 ETM 2 ATM 1"]);
+        check(3, "r U R'", expect!["HTM 3 QTM 3 STM 3 ETM 3 ATM 3"]);
+        check(4, "Rw U Rw'", expect!["HTM 3 QTM 3 STM 3 ETM 3 ATM 3"]);
+        check(4, "r", expect!["HTM 2 QTM 2 STM 1 ETM 1 ATM 1"]);
+        check(5, "3Rw", expect!["HTM 1 QTM 1 STM 1 ETM 1 ATM 1"]);
+        check(5, "2-3Rw2", expect!["HTM 2 QTM 4 STM 1 ETM 1 ATM 1"]);
+        check(5, "R r", expect!["HTM 1 QTM 1 STM 2 ETM 2 ATM 1"]);
+        check(7, "R 3r' L", expect!["HTM 4 QTM 4 STM 3 ETM 3 ATM 1"]);
+    }
+}
@@ -0,0 +1,246 @@
+use crate::{Delimiter, Move, Tree};
+use alloc::{boxed::Box, vec::Vec};
+
+/// The moves of a fewest moves solution, split by the scramble they are
+/// done on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NissParts {
+    /// The moves done on the normal scramble, in the order they are written.
+    pub normal: Vec<Move>,
+    /// The moves done on the inverse scramble, in the order they are written.
+    pub inverse: Vec<Move>,
+}
+
+impl NissParts {
+    /// Returns the solution of the normal scramble, like [`combine_niss`].
+    pub fn solution(&self) -> Vec<Move> {
+        combine_niss(&self.normal, &self.inverse)
+    }
+}
+
+/// Returns the solution of a scramble from the moves found on it and on its
+/// inverse, which are the `normal` moves followed by the inverse of the
+/// `inverse` moves.
+///
+/// Moves at the junction are not cancelled, so `R` and `R'` give `R R`.
+pub fn combine_niss(normal: &[Move], inverse: &[Move]) -> Vec<Move> {
+    let mut result = normal.to_vec();
+    result.extend(inverse.iter().rev().map(|mv| mv.inverse()));
+    result
+}
+
+fn collect_niss(tree: &Tree, invert: bool, on_inverse: bool, parts: &mut NissParts) {
+    match tree {
+        &Tree::Move(mv) => {
+            let moves = if on_inverse {
+                &mut parts.inverse
+            } else {
+                &mut parts.normal
+            };
+            moves.push(if invert { mv.inverse() } else { mv });
+        }
+        Tree::Niss(tree) => collect_niss(tree, invert, !on_inverse, parts),
+        _ => {
+            let mut index = 0;
+            while let Some((child, invert)) = tree.part(invert, index) {
+                collect_niss(child, invert, on_inverse, parts);
+                index += 1;
+            }
+        }
+    }
+}
+
+impl Tree {
+    /// Returns whether the tree has moves done on the inverse scramble.
+    pub(crate) fn has_niss(&self) -> bool {
+        match self {
+            Tree::Niss(_) => true,
+            _ => (0..)
+                .map_while(|index| self.part(false, index))
+                .any(|(tree, _)| tree.has_niss()),
+        }
+    }
+
+    /// Returns the tree with the moves done on the normal and the inverse
+    /// scramble swapped, which is the inverse of the solution it stands for.
+    pub(crate) fn swap_niss(&self) -> Tree {
+        let swap = |tree: &Tree| Box::new(tree.swap_niss());
+        match self {
+            // Parentheses would make a repeat NISS again, so it is written out.
+            Tree::Niss(tree) => match &**tree {
+                Tree::Repeat(tree, count) => {
+                    let tree = if *count < 0 {
+                        tree.inverse()
+                    } else {
+                        (**tree).clone()
+                    };
+                    let copies = count.unsigned_abs() as usize;
+                    Tree::Group(Delimiter::None, alloc::vec![tree; copies])
+                }
+                tree => tree.clone(),
+            },
+            _ if !self.has_niss() => Tree::Niss(Box::new(self.clone())),
+            Tree::Group(delim, trees) => {
+                // Runs of moves done on the normal scramble go on the inverse
+                // together, so `R U (F)` becomes `(R U) F`.
+                let mut result = Vec::new();
+                let mut run = Vec::new();
+                for tree in trees {
+                    if !tree.has_niss() && *tree != Tree::Insertion {
+                        run.push(tree.clone());
+                        continue;
+                    }
+                    if !run.is_empty() {
+                        let group = Tree::Group(Delimiter::None, core::mem::take(&mut run));
+                        result.push(Tree::Niss(Box::new(group)));
+                    }
+                    result.push(match tree {
+                        Tree::Insertion => Tree::Insertion,
+                        _ => tree.swap_niss(),
+                    });
+                }
+                if !run.is_empty() {
+                    result.push(Tree::Niss(Box::new(Tree::Group(Delimiter::None, run))));
+                }
+                Tree::Group(*delim, result)
+            }
+            Tree::Conj(delim, a, b) => Tree::Conj(*delim, swap(a), swap(b)),
+            Tree::Comm(delim, a, b) => Tree::Comm(*delim, swap(a), swap(b)),
+            Tree::Slash(delim, a, b) => Tree::Slash(*delim, swap(a), swap(b)),
+            Tree::Repeat(tree, count) => Tree::Repeat(swap(tree), *count),
+            Tree::Macro(_, tree) => tree.swap_niss(),
+            Tree::Move(_) | Tree::MoveSet(_) | Tree::Insertion => unreachable!(),
+        }
+    }
+
+    /// Returns the moves of the tree split into those done on the normal
+    /// scramble and those in [`Tree::Niss`] parts, done on the inverse.
+    pub fn niss_parts(&self) -> NissParts {
+        let mut parts = NissParts::default();
+        collect_niss(self, false, false, &mut parts);
+        parts
+    }
+
+    /// Returns the moves of the tree as a solution of the normal scramble,
+    /// with the parts done on the inverse scramble inverted at the end.
+    pub fn niss_solution(&self) -> Vec<Move> {
+        self.niss_parts().solution()
+    }
+
+    /// Returns the inverse of the tree as a scramble, followed by the moves
+    /// of `solution` so far, which is where solving continues on the
+    /// inverse scramble.
+    ///
+    /// The moves done on the normal scramble come first inverted, so they
+    /// apply before the scramble on the inverse.
+    pub fn inverse_scramble(&self, solution: &Tree) -> Vec<Move> {
+        let parts = solution.niss_parts();
+        let mut result = combine_niss(&[], &parts.normal);
+        result.extend(self.to_inverse_moves());
+        result.extend(parts.inverse);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{format_moves, parse_alg, Delimiter, ParseMode};
+    use alloc::format;
+    use cube::Cube;
+    use expect_test::{expect, Expect};
+
+    #[test]
+    fn niss() {
+        #[track_caller]
+        fn check(alg: &str, expected: Expect) {
+            let actual = match parse_alg(3, ParseMode::Fmc, alg) {
+                Ok(tree) => {
+                    let parts = tree.niss_parts();
+                    let inverse = tree.inverse();
+                    let solution = tree.niss_solution();
+                    assert_eq!(inverse.niss_solution(), combine_niss(&[], &solution));
+                    let reparsed = parse_alg(3, ParseMode::Fmc, &format!("{inverse}")).unwrap();
+                    assert_eq!(reparsed.niss_solution(), inverse.niss_solution());
+                    format!(
+                        "{tree}\nnormal {}\ninverse {}\nsolution {}\ninverted {inverse}",
+                        format_moves(&parts.normal),
+                        format_moves(&parts.inverse),
+                        format_moves(&solution),
+                    )
+                }
+                Err(e) => format!("{e}"),
+            };
+            expected.assert_eq(&actual);
+        }
+
+        check(
+            "R U (F D') L2 (B)",
+            expect![[r#"
+                R U (F D') L2 (B)
+                normal R U L2
+                inverse F D' B
+                solution R U L2 B' D F'
+                inverted (R U) F D' (L2) B"#]],
+        );
+        check(
+            "(R U)2 [F: (U R)] ^ D",
+            expect![[r#"
+                (R U)2 [F: U R] ^ D
+                normal F U R F' D
+                inverse R U R U
+                solution F U R F' D U' R' U' R'
+                inverted R U R U ([F: U R]) ^ (D)"#]],
+        );
+        check(
+            "^ R (U) ^",
+            expect![[r#"
+                ^ R (U) ^
+                normal R
+                inverse U
+                solution R U'
+                inverted ^ (R) U ^"#]],
+        );
+        check(
+            "(R U)' F",
+            expect![[r#"
+                (R U)' F
+                normal F
+                inverse U' R'
+                solution F R U
+                inverted U' R' (F)"#]],
+        );
+        check(
+            "(R U)2' F",
+            expect![[r#"
+                (R U)2' F
+                normal F
+                inverse U' R' U' R'
+                solution F R U R U
+                inverted U' R' U' R' (F)"#]],
+        );
+        check("(R U", expect!["unclosed '(' at 0..1: expected ')'"]);
+
+        let error = parse_alg(3, ParseMode::Wca, "R ^ U").unwrap_err();
+        expect!["unknown move '^' at 2..3"].assert_eq(&format!("{error}"));
+    }
+
+    #[test]
+    fn inverse_scramble() {
+        let scramble = parse_alg(3, ParseMode::Wca, "R' U' F R2 D B' L U2 F'").unwrap();
+        let solution = parse_alg(3, ParseMode::Fmc, "D' (F L) R2 (U)").unwrap();
+        let inverse = scramble.inverse_scramble(&solution);
+        expect!["R2 D F U2 L' B D' R2 F' U R F L U"].assert_eq(&format_moves(&inverse));
+
+        // Solving the inverse scramble from there finishes the solution.
+        let mut parts = solution.niss_parts();
+        parts
+            .inverse
+            .extend(inverse.iter().rev().map(|mv| mv.inverse()));
+        let moves = [scramble.to_moves(), parts.solution()].concat();
+        let mut cube = Cube::new_solved(3);
+        Tree::Group(Delimiter::None, moves.into_iter().map(Tree::Move).collect())
+            .apply_to(&mut cube);
+        assert_eq!(cube, Cube::new_solved(3));
+    }
+}
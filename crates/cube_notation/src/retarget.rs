@@ -0,0 +1,270 @@
+use crate::{Move, Tree};
+use cube::Face;
+
+/// The layers of a move, described without the size of the cube.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Layers {
+    /// The layers from `start` up to `end`, counted from the face like the
+    /// layers of a [`Move`], which reach at most the middle layer.
+    Outer { start: u16, end: u16 },
+    /// All layers except the two outer ones, like `M`.
+    Slice,
+    /// The single middle layer of an odd cube, like `m`.
+    Middle,
+    /// The layers from the face up to the middle, including the middle layer
+    /// of an odd cube. [`Move::generic`] reads such moves as
+    /// [`Layers::Outer`], like parsing the same move on another cube would.
+    WideToMiddle,
+    /// All layers, like `x`.
+    All,
+    /// The layers from `start` up to `end` of an `n` by `n` cube, which
+    /// reach the middle or the far side and mean something else on other
+    /// cubes, like `3Rw` on a 4x4.
+    Sized { n: u16, start: u16, end: u16 },
+}
+
+/// A move that can be done on cubes of any size where its layers exist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GenericMove {
+    pub face: Face,
+    pub layers: Layers,
+    pub count: u8,
+}
+
+impl Move {
+    /// Returns the move without its cube size. Slices, middle layers and
+    /// rotations keep their meaning on other cubes, and moves up to the
+    /// middle keep their layers counted from the face, so that retargeting
+    /// a move gives the same move as parsing it on the other cube.
+    pub fn generic(self) -> GenericMove {
+        let n = self.n;
+        let layers = match (self.start, self.end) {
+            (0, end) if end == n => Layers::All,
+            (1, end) if n >= 3 && end == n - 1 => Layers::Slice,
+            (start, end) if n % 2 == 1 && start == n / 2 && end == n / 2 + 1 => Layers::Middle,
+            (start, end) if end <= n.div_ceil(2) => Layers::Outer { start, end },
+            (start, end) => Layers::Sized { n, start, end },
+        };
+        GenericMove {
+            face: self.face,
+            layers,
+            count: self.count,
+        }
+    }
+}
+
+impl GenericMove {
+    /// Returns the move on an `n` by `n` cube, or `None` if the cube does not
+    /// have its layers.
+    pub fn resolve(self, n: u16) -> Option<Move> {
+        let (start, end) = match self.layers {
+            Layers::Outer { start, end } if end <= n.div_ceil(2) => (start, end),
+            Layers::Slice if n >= 3 => (1, n - 1),
+            Layers::Middle if n % 2 == 1 && n >= 3 => (n / 2, n / 2 + 1),
+            Layers::WideToMiddle if n >= 2 => (0, n.div_ceil(2)),
+            Layers::All => (0, n),
+            Layers::Sized {
+                n: size,
+                start,
+                end,
+            } if size == n => (start, end),
+            _ => return None,
+        };
+        Some(Move {
+            n,
+            face: self.face,
+            start,
+            end,
+            count: self.count,
+        })
+    }
+
+    /// Returns the size of the smallest cube with the layers of the move.
+    pub fn min_size(self) -> u16 {
+        match self.layers {
+            Layers::Outer { end, .. } => (2 * end - 1).max(2),
+            Layers::Slice | Layers::Middle => 3,
+            Layers::WideToMiddle | Layers::All => 2,
+            Layers::Sized { n, .. } => n,
+        }
+    }
+}
+
+impl Tree {
+    /// Calls `f` with every move in the order it is written, including the
+    /// moves of move sets and macros.
+    pub(crate) fn visit_written_moves(&self, f: &mut impl FnMut(Move)) {
+        match self {
+            &Tree::Move(mv) => f(mv),
+            Tree::Group(_, trees) => trees.iter().for_each(|tree| tree.visit_written_moves(f)),
+            Tree::Conj(_, a, b) | Tree::Comm(_, a, b) | Tree::Slash(_, a, b) => {
+                a.visit_written_moves(f);
+                b.visit_written_moves(f);
+            }
+            Tree::Repeat(tree, _) => tree.visit_written_moves(f),
+            Tree::MoveSet(moves) => moves.iter().copied().for_each(f),
+            Tree::Macro(_, tree) | Tree::Niss(tree) => tree.visit_written_moves(f),
+            Tree::Insertion => {}
+        }
+    }
+
+    /// Returns the same alg on an `n` by `n` cube, with every move converted
+    /// through [`Move::generic`], or `None` if a move does not fit on it.
+    pub fn retarget(&self, n: u16) -> Option<Tree> {
+        let mut fits = true;
+        self.visit_written_moves(&mut |mv| fits &= mv.generic().resolve(n).is_some());
+        if !fits {
+            return None;
+        }
+        Some(self.map_moves(&|mv| mv.generic().resolve(n).unwrap()))
+    }
+
+    /// Returns the size of the smallest cube the tree can be
+    /// [retargeted](Tree::retarget) to. Trees with [`Layers::Sized`] moves
+    /// can only be retargeted to the size of those moves.
+    pub fn min_size(&self) -> u16 {
+        let mut size = 2;
+        let mut middle = false;
+        self.visit_written_moves(&mut |mv| {
+            let mv = mv.generic();
+            size = size.max(mv.min_size());
+            middle |= mv.layers == Layers::Middle;
+        });
+        if middle && size % 2 == 0 {
+            size += 1;
+        }
+        size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_alg, ParseMode};
+    use alloc::{format, string::String};
+    use expect_test::{expect, Expect};
+
+    #[test]
+    fn retarget() {
+        #[track_caller]
+        fn check(n: u16, alg: &str, expected: Expect) {
+            let tree = parse_alg(n, ParseMode::Wca, alg).unwrap();
+            let mut actual = format!("min {}", tree.min_size());
+            for size in 2..8 {
+                actual += &match tree.retarget(size) {
+                    Some(tree) => format!("\n{size}: {tree}"),
+                    None => String::new(),
+                };
+            }
+            expected.assert_eq(&actual);
+        }
+
+        check(
+            3,
+            "[R, U]",
+            expect![[r#"
+            min 2
+            2: [R, U]
+            3: [R, U]
+            4: [R, U]
+            5: [R, U]
+            6: [R, U]
+            7: [R, U]"#]],
+        );
+        check(
+            3,
+            "M2 U M2 U2 x",
+            expect![[r#"
+            min 3
+            3: M2 U M2 U2 x
+            4: M2 U M2 U2 x
+            5: M2 U M2 U2 x
+            6: M2 U M2 U2 x
+            7: M2 U M2 U2 x"#]],
+        );
+        check(
+            4,
+            "[r: U2] Rw",
+            expect![[r#"
+            min 3
+            3: [M': U2] Rw
+            4: [r: U2] Rw
+            5: [r: U2] Rw
+            6: [r: U2] Rw
+            7: [r: U2] Rw"#]],
+        );
+        check(
+            3,
+            "Rw U Rw'",
+            expect![[r#"
+            min 3
+            3: Rw U Rw'
+            4: Rw U Rw'
+            5: Rw U Rw'
+            6: Rw U Rw'
+            7: Rw U Rw'"#]],
+        );
+        check(
+            4,
+            "Rw U Rw'",
+            expect![[r#"
+            min 3
+            3: Rw U Rw'
+            4: Rw U Rw'
+            5: Rw U Rw'
+            6: Rw U Rw'
+            7: Rw U Rw'"#]],
+        );
+        check(
+            4,
+            "3Rw",
+            expect![[r#"
+            min 4
+            4: 3Rw"#]],
+        );
+        check(
+            5,
+            "m' <R, u>",
+            expect![[r#"
+            min 3
+            3: M' <R, E'>
+            5: m' <R, u>
+            7: m' <R, u>"#]],
+        );
+        check(
+            7,
+            "[3r' E, 2-3Lw]",
+            expect![[r#"
+            min 5
+            5: [m E, 2-3l]
+            6: [3r' E, 2-3l]
+            7: [3r' E, 2-3l]"#]],
+        );
+
+        // Moves written the same way on every cube retarget like parsing
+        // the alg again.
+        for (n, alg) in [
+            (3, "Rw U Rw'"),
+            (4, "Rw U Rw'"),
+            (3, "M2 U M2 U2 x"),
+            (4, "[Uw, R2] y' Dw2"),
+            (5, "3Rw U 3Rw' 2R"),
+            (7, "[4Fw: 3-4r] 2-2Lw"),
+        ] {
+            let tree = parse_alg(n, ParseMode::Wca, alg).unwrap();
+            for size in tree.min_size()..8 {
+                let retargeted = tree.retarget(size).unwrap();
+                let parsed = parse_alg(size, ParseMode::Wca, alg).unwrap();
+                assert_eq!(retargeted, parsed, "{alg} on {size}");
+            }
+        }
+
+        let mv = GenericMove {
+            face: Face::R,
+            layers: Layers::WideToMiddle,
+            count: 1,
+        };
+        let moves = [mv.resolve(4).unwrap(), mv.resolve(5).unwrap()];
+        expect!["[Rw, 3Rw]"].assert_eq(&format!("{moves:?}"));
+    }
+}
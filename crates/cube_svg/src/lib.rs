@@ -0,0 +1,241 @@
+//! This crate renders the state of a [`Cube`] as an SVG image.
+//!
+//! The default [`View::Net`] draws the unfolded net of all six faces, and
+//! [`View::Isometric`] draws the U, F and R faces as seen from the top front
+//! right corner. Both are built on [`Cube::color_at`], so they work for any n.
+
+use cube::{for_each_face_sticker, ColorScheme, Cube, Face, StickerMask, StickerState};
+use std::fmt::Write;
+
+#[cfg(test)]
+mod tests;
+
+/// Where each face of the unfolded net goes, in units of whole faces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetLayout {
+    /// The column and row of each face, indexed by [`Face::index`].
+    pub offsets: [(u16, u16); 6],
+}
+
+impl NetLayout {
+    /// L, F, R and B in a row, with U above and D below F.
+    pub const CROSS: NetLayout = NetLayout {
+        offsets: [(1, 0), (0, 1), (1, 1), (2, 1), (3, 1), (1, 2)],
+    };
+
+    /// Like [`NetLayout::CROSS`], but with B below D.
+    pub const VERTICAL: NetLayout = NetLayout {
+        offsets: [(1, 0), (0, 1), (1, 1), (2, 1), (1, 3), (1, 2)],
+    };
+
+    fn size(&self) -> (u16, u16) {
+        self.offsets
+            .iter()
+            .fold((0, 0), |(w, h), &(x, y)| (w.max(x + 1), h.max(y + 1)))
+    }
+}
+
+impl Default for NetLayout {
+    fn default() -> Self {
+        NetLayout::CROSS
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    /// All six faces, unfolded.
+    Net(NetLayout),
+    /// The U, F and R faces as seen from the top front right corner.
+    Isometric,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+    pub view: View,
+    /// The size of a sticker, including the gap around it.
+    pub sticker_size: f32,
+    /// The space between neighboring stickers.
+    pub gap: f32,
+    /// Stickers to grey out or outline. Must match the size of the cube.
+    pub mask: Option<StickerMask>,
+    pub highlight_color: String,
+    pub color_scheme: ColorScheme,
+}
+
+impl Default for SvgOptions {
+    fn default() -> SvgOptions {
+        SvgOptions {
+            view: View::Net(NetLayout::CROSS),
+            sticker_size: 20.0,
+            gap: 2.0,
+            mask: None,
+            highlight_color: StickerMask::HIGHLIGHT.to_string(),
+            color_scheme: ColorScheme::WESTERN,
+        }
+    }
+}
+
+/// Rounds to two decimal places to keep the output readable.
+fn num(x: f32) -> f32 {
+    (x * 100.0).round() / 100.0
+}
+
+/// Renders `cube` as a standalone SVG document.
+pub fn render(cube: &Cube, options: &SvgOptions) -> String {
+    match options.view {
+        View::Net(layout) => render_net(cube, &layout, options),
+        View::Isometric => render_isometric(cube, options),
+    }
+}
+
+fn sticker_style(cube: &Cube, face: Face, x: i16, y: i16, options: &SvgOptions) -> String {
+    let state = options
+        .mask
+        .as_ref()
+        .map_or(StickerState::Visible, |mask| mask.get(face, x, y));
+    let color = state.color(options.color_scheme.color(cube.color_at(face, x, y)));
+    let mut style = format!(r#"fill="{color}""#);
+    if state == StickerState::Highlighted {
+        write!(
+            &mut style,
+            r#" stroke="{}" stroke-width="{}""#,
+            options.highlight_color,
+            num(options.sticker_size / 10.0),
+        )
+        .unwrap();
+    }
+    style
+}
+
+fn render_net(cube: &Cube, layout: &NetLayout, options: &SvgOptions) -> String {
+    let n = f32::from(cube.n);
+    let size = options.sticker_size;
+    let (columns, rows) = layout.size();
+    let width = num(f32::from(columns) * n * size);
+    let height = num(f32::from(rows) * n * size);
+
+    let mut s = String::new();
+    writeln!(
+        &mut s,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#,
+    )
+    .unwrap();
+
+    for face in Face::ALL {
+        let (x_offset, y_offset) = layout.offsets[face.index()];
+        let left = f32::from(x_offset) * n * size;
+        let top = f32::from(y_offset) * n * size;
+        writeln!(
+            &mut s,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="black"/>"#,
+            num(left),
+            num(top),
+            num(n * size),
+            num(n * size),
+        )
+        .unwrap();
+
+        for_each_face_sticker(cube.n, |x, y, column, row| {
+            writeln!(
+                &mut s,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" {}/>"#,
+                num(left + f32::from(column) * size + options.gap / 2.0),
+                num(top + f32::from(row) * size + options.gap / 2.0),
+                num(size - options.gap),
+                num(size - options.gap),
+                sticker_style(cube, face, x, y, options),
+            )
+            .unwrap();
+        });
+    }
+
+    s.push_str("</svg>\n");
+    s
+}
+
+/// Projects a point to the screen, with x following R, y following U and z
+/// following F.
+fn project(x: f32, y: f32, z: f32) -> (f32, f32) {
+    const COS_30: f32 = 0.866_025_4;
+    ((x - z) * COS_30, (x + z) / 2.0 - y)
+}
+
+/// Returns the point on `face` at `u` to the right of and `v` above its center,
+/// for a cube with faces `half_n` away from its center.
+fn point_on_face(face: Face, half_n: f32, u: f32, v: f32) -> (f32, f32, f32) {
+    match face {
+        Face::U => (u, half_n, -v),
+        Face::F => (u, v, half_n),
+        Face::R => (half_n, v, -u),
+        Face::L | Face::B | Face::D => unreachable!("face is not visible"),
+    }
+}
+
+fn write_polygon(s: &mut String, points: [(f32, f32, f32); 4], size: f32, style: &str) {
+    s.push_str(r#"<polygon points=""#);
+    for (i, (x, y, z)) in points.into_iter().enumerate() {
+        let (px, py) = project(x * size, y * size, z * size);
+        if i != 0 {
+            s.push(' ');
+        }
+        write!(s, "{},{}", num(px), num(py)).unwrap();
+    }
+    writeln!(s, r#"" {style}/>"#).unwrap();
+}
+
+fn render_isometric(cube: &Cube, options: &SvgOptions) -> String {
+    let n = f32::from(cube.n);
+    let half_n = n / 2.0;
+    let size = options.sticker_size;
+    let (half_width, _) = project(half_n * size, 0.0, -half_n * size);
+    let half_height = n * size;
+
+    let mut s = String::new();
+    writeln!(
+        &mut s,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+        num(2.0 * half_width),
+        num(2.0 * half_height),
+        num(-half_width),
+        num(-half_height),
+        num(2.0 * half_width),
+        num(2.0 * half_height),
+    )
+    .unwrap();
+
+    for face in [Face::U, Face::F, Face::R] {
+        let corner = |u: f32, v: f32| point_on_face(face, half_n, u, v);
+        write_polygon(
+            &mut s,
+            [
+                corner(-half_n, half_n),
+                corner(half_n, half_n),
+                corner(half_n, -half_n),
+                corner(-half_n, -half_n),
+            ],
+            size,
+            r#"fill="black""#,
+        );
+
+        let inset = 0.5 - options.gap / size / 2.0;
+        for_each_face_sticker(cube.n, |x, y, column, row| {
+            let u = f32::from(column) + 0.5 - half_n;
+            let v = half_n - f32::from(row) - 0.5;
+            let corner = |du: f32, dv: f32| point_on_face(face, half_n, u + du, v + dv);
+            write_polygon(
+                &mut s,
+                [
+                    corner(-inset, inset),
+                    corner(inset, inset),
+                    corner(inset, -inset),
+                    corner(-inset, -inset),
+                ],
+                size,
+                &sticker_style(cube, face, x, y, options),
+            );
+        });
+    }
+
+    s.push_str("</svg>\n");
+    s
+}
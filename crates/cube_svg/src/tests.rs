@@ -0,0 +1,105 @@
+use super::*;
+use expect_test::expect;
+
+#[test]
+fn net_1x1x1() {
+    expect![[r##"
+        <svg xmlns="http://www.w3.org/2000/svg" width="80" height="60" viewBox="0 0 80 60">
+        <rect x="20" y="0" width="20" height="20" fill="black"/>
+        <rect x="21" y="1" width="18" height="18" fill="#ffffff"/>
+        <rect x="0" y="20" width="20" height="20" fill="black"/>
+        <rect x="1" y="21" width="18" height="18" fill="#ff7f00"/>
+        <rect x="20" y="20" width="20" height="20" fill="black"/>
+        <rect x="21" y="21" width="18" height="18" fill="#00ff00"/>
+        <rect x="40" y="20" width="20" height="20" fill="black"/>
+        <rect x="41" y="21" width="18" height="18" fill="#ff0000"/>
+        <rect x="60" y="20" width="20" height="20" fill="black"/>
+        <rect x="61" y="21" width="18" height="18" fill="#0000ff"/>
+        <rect x="20" y="40" width="20" height="20" fill="black"/>
+        <rect x="21" y="41" width="18" height="18" fill="#ffff00"/>
+        </svg>
+    "##]]
+    .assert_eq(&render(&Cube::new_solved(1), &SvgOptions::default()));
+}
+
+#[test]
+fn net_2x2x2_highlight() {
+    let mut cube = Cube::new_solved(2);
+    cube.rotate_face(Face::R, 1);
+    let mut mask = StickerMask::new(2);
+    mask.set(Face::U, 1, 1, StickerState::Highlighted);
+    let options = SvgOptions {
+        view: View::Net(NetLayout::VERTICAL),
+        sticker_size: 10.0,
+        gap: 0.0,
+        mask: Some(mask),
+        ..SvgOptions::default()
+    };
+    let svg = render(&cube, &options);
+    expect![[r##"
+        <svg xmlns="http://www.w3.org/2000/svg" width="60" height="80" viewBox="0 0 60 80">
+        <rect x="20" y="0" width="20" height="20" fill="black"/>
+        <rect x="20" y="0" width="10" height="10" fill="#ffffff"/>
+        <rect x="30" y="0" width="10" height="10" fill="#00ff00" stroke="#ff00ff" stroke-width="1"/>
+        <rect x="20" y="10" width="10" height="10" fill="#ffffff"/>
+        <rect x="30" y="10" width="10" height="10" fill="#00ff00"/>"##]]
+    .assert_eq(&svg.lines().take(6).collect::<Vec<_>>().join("\n"));
+    assert_eq!(svg.matches("<rect").count(), 6 + 24);
+}
+
+#[test]
+fn isometric() {
+    for n in 1..=7 {
+        let options = SvgOptions {
+            view: View::Isometric,
+            ..SvgOptions::default()
+        };
+        let svg = render(&Cube::new_solved(n), &options);
+        let stickers = usize::from(n) * usize::from(n);
+        assert_eq!(svg.matches("<polygon").count(), 3 + 3 * stickers);
+        assert_eq!(svg.matches("#ffffff").count(), stickers);
+        assert_eq!(svg.matches("#00ff00").count(), stickers);
+        assert_eq!(svg.matches("#ff0000").count(), stickers);
+    }
+
+    expect![[r##"
+        <svg xmlns="http://www.w3.org/2000/svg" width="34.64" height="40" viewBox="-17.32 -20 34.64 40">
+        <polygon points="0,-20 17.32,-10 0,0 -17.32,-10" fill="black"/>
+        <polygon points="0,-19 15.59,-10 0,-1 -15.59,-10" fill="#ffffff"/>
+        <polygon points="-17.32,-10 0,0 0,20 -17.32,10" fill="black"/>
+        <polygon points="-16.45,-8.5 -0.87,0.5 -0.87,18.5 -16.45,9.5" fill="#00ff00"/>
+        <polygon points="0,0 17.32,-10 17.32,10 0,20" fill="black"/>
+        <polygon points="0.87,0.5 16.45,-8.5 16.45,9.5 0.87,18.5" fill="#ff0000"/>
+        </svg>
+    "##]]
+    .assert_eq(&render(
+        &Cube::new_solved(1),
+        &SvgOptions {
+            view: View::Isometric,
+            ..SvgOptions::default()
+        },
+    ));
+}
+
+#[test]
+fn color_scheme() {
+    let options = SvgOptions {
+        color_scheme: ColorScheme::JAPANESE,
+        ..SvgOptions::default()
+    };
+    let svg = render(&Cube::new_solved(1), &options);
+    let d = svg.lines().nth(12).unwrap();
+    expect![[r##"<rect x="21" y="41" width="18" height="18" fill="#0000ff"/>"##]].assert_eq(d);
+}
+
+#[test]
+fn mask() {
+    let options = SvgOptions {
+        mask: Some(StickerMask::last_layer(3)),
+        ..SvgOptions::default()
+    };
+    let svg = render(&Cube::new_solved(3), &options);
+    assert_eq!(svg.matches("#808080").count(), 54 - 21);
+    assert_eq!(svg.matches("#ffffff").count(), 9);
+    assert_eq!(svg.matches("#00ff00").count(), 3);
+}